@@ -1,6 +1,10 @@
 use {
     crate::{EvaluateInterpreterResult, InterpreterError, Token, Value},
-    std::{cell::RefCell, collections::HashMap, rc::Rc},
+    std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        rc::Rc,
+    },
 };
 
 // Thanks https://github.com/Pvlerick/codecrafters-interpreter-rust/blob/master/src/environment.rs
@@ -10,6 +14,12 @@ pub struct Environment {
     inner: Rc<RefCell<Inner>>,
 }
 
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Environment {
     pub fn new() -> Self {
         Self {
@@ -27,19 +37,71 @@ impl Environment {
         self.inner.borrow_mut().define(name, value);
     }
 
+    /// Define a variable that can't be assigned afterwards.
+    pub fn define_constant(&mut self, name: String, value: Value) {
+        self.inner.borrow_mut().define_constant(name, value);
+    }
+
     pub fn assign(&mut self, name: &Token, value: &Value) -> Result<(), InterpreterError> {
         self.inner.borrow_mut().assign(name, value)
     }
 
     pub fn get(&self, name: &Token) -> EvaluateInterpreterResult {
-        self.inner.borrow_mut().get(name)
+        self.inner.borrow().get(name)
     }
+
+    /// Copy the variables of this scope and all the enclosing ones.
+    /// Only the bindings are copied, lists, maps and functions stay shared.
+    pub fn snapshot(&self) -> EnvironmentSnapshot {
+        let mut scopes = Vec::new();
+        let mut current = Some(self.inner.clone());
+
+        while let Some(inner) = current {
+            let inner = inner.borrow();
+            scopes.push((inner.values.clone(), inner.constants.clone()));
+            current = inner.enclosing.clone();
+        }
+
+        EnvironmentSnapshot { scopes }
+    }
+
+    /// Put back the variables of a snapshot taken from this environment.
+    /// Scopes are restored in place, so closures capturing them see the restored variables too.
+    pub fn restore(&mut self, snapshot: EnvironmentSnapshot) {
+        let mut current = Some(self.inner.clone());
+
+        for (values, constants) in snapshot.scopes {
+            let Some(inner) = current else {
+                break;
+            };
+
+            let mut inner = inner.borrow_mut();
+            inner.values = values;
+            inner.constants = constants;
+            current = inner.enclosing.clone();
+        }
+    }
+}
+
+/// The variables of an environment chain at some point, see `Environment::snapshot`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentSnapshot {
+    /// Variables and constant names of each scope, innermost first.
+    scopes: Vec<(HashMap<String, Value>, HashSet<String>)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Inner {
     enclosing: Option<Rc<RefCell<Inner>>>,
     values: HashMap<String, Value>,
+    /// Names of the `values` declared with `const`.
+    constants: HashSet<String>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Inner {
@@ -47,6 +109,7 @@ impl Inner {
         Inner {
             enclosing: None,
             values: HashMap::new(),
+            constants: HashSet::new(),
         }
     }
 
@@ -54,17 +117,33 @@ impl Inner {
         Self {
             enclosing: Some(inner.clone()),
             values: HashMap::new(),
+            constants: HashSet::new(),
         }
     }
 
     pub fn define(&mut self, name: String, value: Value) {
+        // redeclaring a constant with `var` makes it a regular variable again
+        self.constants.remove(&name);
+        self.values.insert(name, value);
+    }
+
+    pub fn define_constant(&mut self, name: String, value: Value) {
+        self.constants.insert(name.clone());
         self.values.insert(name, value);
     }
 
     pub fn assign(&mut self, name: &Token, value: &Value) -> Result<(), InterpreterError> {
         let lexeme = &name.lexeme;
-        if self.values.contains_key(lexeme) {
-            self.values.insert(lexeme.clone(), value.clone());
+        if self.constants.contains(lexeme) {
+            return Err(InterpreterError {
+                token: Some(name.clone()),
+                message: format!("Cannot reassign constant '{lexeme}'."),
+            });
+        }
+
+        // overwrite in place, re-inserting would allocate a new key on every assignment
+        if let Some(slot) = self.values.get_mut(lexeme) {
+            *slot = value.clone();
             return Ok(());
         }
 
@@ -94,3 +173,36 @@ impl Inner {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenType;
+
+    fn name(lexeme: &str) -> Token {
+        Token::new(
+            TokenType::Identifier,
+            lexeme.into(),
+            None,
+            1,
+            0,
+            lexeme.len(),
+        )
+    }
+
+    #[test]
+    fn restore_forgets_variables_defined_after_the_snapshot() {
+        let mut environment = Environment::new();
+        environment.define("a".into(), Value::Integer(1));
+
+        let snapshot = environment.snapshot();
+        environment.define("b".into(), Value::Integer(2));
+        environment.assign(&name("a"), &Value::Integer(3)).unwrap();
+        environment.restore(snapshot);
+
+        assert_eq!(environment.get(&name("a")).unwrap(), Value::Integer(1));
+
+        let error = environment.get(&name("b")).unwrap_err();
+        assert_eq!(error.message, "Undefined variable 'b'.");
+    }
+}
@@ -30,6 +30,36 @@ pub enum Expression {
         parenthesis: Token,
         arguments: Vec<Expression>,
     },
+    Get {
+        object: Box<Expression>,
+        name: Token,
+    },
+    List {
+        bracket: Token,
+        elements: Vec<Expression>,
+    },
+    Map {
+        brace: Token,
+        entries: Vec<(Expression, Expression)>,
+    },
+    Index {
+        object: Box<Expression>,
+        bracket: Token,
+        index: Box<Expression>,
+    },
+    SetIndex {
+        object: Box<Expression>,
+        bracket: Token,
+        index: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// `++` or `--` on a variable or an index.
+    /// Evaluates to the new value when `prefix`, and to the old one otherwise.
+    Update {
+        operator: Token,
+        target: Box<Expression>,
+        prefix: bool,
+    },
 }
 
 impl fmt::Display for Expression {
@@ -46,7 +76,44 @@ impl fmt::Display for Expression {
             Expression::Variable(name) => write!(f, "(var {})", name.lexeme),
             Expression::Assign { name, right } => write!(f, "(assign {} {right})", name.lexeme),
             Expression::Logical { left, operator, right } => write!(f, "(logical {} {left} {right})", operator.lexeme),
-            Expression::Call { callee, parenthesis, arguments } => write!(f, "(call {callee} {parenthesis} {arguments:?})"),
+            Expression::Call { callee, parenthesis: _, arguments } => {
+                write!(f, "(call {callee}")?;
+
+                for argument in arguments {
+                    write!(f, " {argument}")?;
+                }
+
+                write!(f, ")")
+            }
+            Expression::Get { object, name } => write!(f, "(get {object} {})", name.lexeme),
+            Expression::List {
+                bracket: _,
+                elements,
+            } => {
+                write!(f, "(list")?;
+
+                for element in elements {
+                    write!(f, " {element}")?;
+                }
+
+                write!(f, ")")
+            }
+            Expression::Map { brace: _, entries } => {
+                write!(f, "(map")?;
+
+                for (key, value) in entries {
+                    write!(f, " ({key} {value})")?;
+                }
+
+                write!(f, ")")
+            }
+            Expression::Index { object, bracket: _, index } => write!(f, "(index {object} {index})"),
+            Expression::SetIndex { object, bracket: _, index, right } => write!(f, "(set-index {object} {index} {right})"),
+            Expression::Update { operator, target, prefix } => {
+                let position = if *prefix { "prefix" } else { "postfix" };
+
+                write!(f, "({position} {} {target})", operator.lexeme)
+            }
         }
     }
 }
@@ -1,7 +1,13 @@
-use crate::{Environment, ExecuteInterpreterResult, Interpreter, Statement, Token, Value};
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
+
+use crate::{
+    Environment, ExecuteInterpreterResult, Interpreter, Parameter, Statement, Token, Value,
+};
 
 pub trait Callable: std::fmt::Debug {
-    fn arity(&self) -> usize;
+    /// How many arguments the callable accepts, from the required ones up to the optional ones.
+    /// An end of `usize::MAX` accepts any number of extra arguments.
+    fn arity(&self) -> RangeInclusive<usize>;
     fn call(
         &self,
         interpreter: &mut Interpreter,
@@ -14,7 +20,7 @@ pub trait Callable: std::fmt::Debug {
 #[derive(Debug, PartialEq)]
 pub struct LoxFunction {
     pub name: Token,
-    pub parameters: Vec<Token>,
+    pub parameters: Vec<Parameter>,
     pub body: Vec<Statement>,
     pub closure: Environment,
 }
@@ -26,8 +32,17 @@ impl LoxFunction {
 }
 
 impl super::Callable for LoxFunction {
-    fn arity(&self) -> usize {
-        self.parameters.len()
+    fn arity(&self) -> RangeInclusive<usize> {
+        let required = self
+            .parameters
+            .iter()
+            .take_while(|parameter| parameter.default.is_none() && !parameter.rest)
+            .count();
+
+        match self.parameters.last() {
+            Some(parameter) if parameter.rest => required..=usize::MAX,
+            _ => required..=self.parameters.len(),
+        }
     }
 
     fn call(
@@ -37,9 +52,34 @@ impl super::Callable for LoxFunction {
         _: Token,
     ) -> ExecuteInterpreterResult {
         let mut environment = self.closure.enclose();
+        let mut arguments = arguments.into_iter();
+
+        for parameter in &self.parameters {
+            if parameter.rest {
+                let rest: Vec<Value> = arguments.by_ref().collect();
+                environment.define(
+                    parameter.name.lexeme.clone(),
+                    Value::List(Rc::new(RefCell::new(rest))),
+                );
+
+                break;
+            }
+
+            let value = match (arguments.next(), &parameter.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => {
+                    // defaults are evaluated in the call's environment, so they can use the previous parameters
+                    let previous =
+                        std::mem::replace(&mut interpreter.environment, environment.clone());
+                    let value = interpreter.evaluate(default.clone());
+                    interpreter.environment = previous;
+
+                    value?
+                }
+                (None, None) => unreachable!("arity is checked before calling"),
+            };
 
-        for (parameter, value) in self.parameters.iter().zip(arguments.into_iter()) {
-            environment.define(parameter.lexeme.clone(), value);
+            environment.define(parameter.name.lexeme.clone(), value);
         }
 
         let returned = interpreter.execute_block(self.body.clone(), environment)?;
@@ -53,33 +93,1126 @@ impl super::Callable for LoxFunction {
 
 pub mod native {
     use crate::{ExecuteInterpreterResult, Interpreter, InterpreterError, Token, Value};
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::{
+        cell::RefCell,
+        fs,
+        io::{self, IsTerminal, Write},
+        ops::RangeInclusive,
+        rc::Rc,
+        thread,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    /// Seconds since the Unix epoch, with their fraction, the default `Interpreter::clock`.
+    /// A system clock set before the epoch reads as 0.
+    pub fn system_clock() -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |duration| duration.as_secs_f64())
+    }
 
     #[derive(Debug, PartialEq)]
     pub struct ClockFunction {}
 
     impl super::Callable for ClockFunction {
-        fn arity(&self) -> usize {
-            0
+        fn arity(&self) -> RangeInclusive<usize> {
+            0..=0
         }
 
         fn call(
             &self,
-            _: &mut Interpreter,
+            interpreter: &mut Interpreter,
             _: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::Number((interpreter.clock)())))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "clock")
+        }
+    }
+
+    /// Signature of a native function registered by the host.
+    pub type HostFunction =
+        dyn Fn(&mut Interpreter, Vec<Value>, &Token) -> ExecuteInterpreterResult;
+
+    /// A native function registered by the host, see `Interpreter::define_native`.
+    pub struct HostNativeFunction {
+        pub name: String,
+        pub arity: usize,
+        pub function: Box<HostFunction>,
+    }
+
+    impl std::fmt::Debug for HostNativeFunction {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("HostNativeFunction")
+                .field("name", &self.name)
+                .field("arity", &self.arity)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl super::Callable for HostNativeFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            self.arity..=self.arity
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            (self.function)(interpreter, arguments, &token)
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", self.name)
+        }
+    }
+
+    /// Largest integer a `f64` can represent exactly, like JavaScript's `Number.MAX_SAFE_INTEGER`.
+    pub const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+    fn is_safe_integer(value: f64) -> bool {
+        value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct IsIntegerFunction {}
+
+    impl super::Callable for IsIntegerFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            let is_integer = match arguments[0] {
+                Value::Number(value) => is_safe_integer(value),
+                Value::Integer(value) => is_safe_integer(value as f64),
+                _ => false,
+            };
+
+            Ok(Some(Value::Boolean(is_integer)))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "is_integer")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct AsIntegerFunction {}
+
+    impl super::Callable for AsIntegerFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            match arguments[0] {
+                Value::Number(value) if is_safe_integer(value) => {
+                    Ok(Some(Value::Integer(value as i64)))
+                }
+                Value::Integer(value) if is_safe_integer(value as f64) => {
+                    Ok(Some(Value::Integer(value)))
+                }
+                Value::Number(_) | Value::Integer(_) => Err(InterpreterError {
+                    token: Some(token),
+                    message: format!("{} is not an integer.", arguments[0]),
+                }),
+                _ => Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must be a number.".into(),
+                }),
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "as_integer")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct ToStringFunction {}
+
+    impl super::Callable for ToStringFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::String(Rc::new(arguments[0].to_string()))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "to_string")
+        }
+    }
+
+    /// Numbers are returned as is, strings are parsed, and anything that is not a finite number gives `nil`.
+    #[derive(Debug, PartialEq)]
+    pub struct ToNumberFunction {}
+
+    impl super::Callable for ToNumberFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            let value = match &arguments[0] {
+                Value::Number(_) | Value::Integer(_) => arguments[0].clone(),
+                Value::String(value) => parse_number(value).unwrap_or(Value::Nil),
+                _ => Value::Nil,
+            };
+
+            Ok(Some(value))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "to_number")
+        }
+    }
+
+    /// The finite number written in a string, surrounding whitespace aside.
+    fn parse_number(text: &str) -> Option<Value> {
+        match (text.trim().parse::<i64>(), text.trim().parse::<f64>()) {
+            (Ok(value), _) => Some(Value::Integer(value)),
+            (_, Ok(value)) if value.is_finite() => Some(Value::Number(value)),
+            _ => None,
+        }
+    }
+
+    /// Parse a string into a `[number, true]` list, or `[nil, false]` when it isn't a number.
+    /// Unlike `to_number`, the success is explicit and only strings are accepted.
+    #[derive(Debug, PartialEq)]
+    pub struct ParseNumberFunction {}
+
+    impl super::Callable for ParseNumberFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Value::String(text) = &arguments[0] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must be a string.".into(),
+                });
+            };
+
+            let result = match parse_number(text) {
+                Some(number) => vec![number, Value::Boolean(true)],
+                None => vec![Value::Nil, Value::Boolean(false)],
+            };
+
+            Ok(Some(Value::List(Rc::new(RefCell::new(result)))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "parse_number")
+        }
+    }
+
+    /// Wrap a value's text in an ANSI color: `red`, `green`, `yellow`, `blue` or `reset`.
+    /// The text is returned untouched when colors are disabled, see `Interpreter::colors`.
+    #[derive(Debug, PartialEq)]
+    pub struct ColorFunction {}
+
+    impl super::Callable for ColorFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=2
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Value::String(name) = &arguments[1] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Color name must be a string.".into(),
+                });
+            };
+
+            let code = match name.as_str() {
+                "red" => 31,
+                "green" => 32,
+                "yellow" => 33,
+                "blue" => 34,
+                "reset" => 0,
+                _ => {
+                    return Err(InterpreterError {
+                        token: Some(token),
+                        message: format!("Unknown color '{name}'."),
+                    })
+                }
+            };
+
+            let text = &arguments[0];
+            let enabled = interpreter
+                .colors
+                .unwrap_or_else(|| io::stdout().is_terminal());
+
+            let output = if enabled {
+                format!("\x1b[{code}m{text}\x1b[0m")
+            } else {
+                text.to_string()
+            };
+
+            Ok(Some(Value::String(Rc::new(output))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "color")
+        }
+    }
+
+    /// Pause the program for a number of seconds, fractions included.
+    #[derive(Debug, PartialEq)]
+    pub struct SleepFunction {}
+
+    impl super::Callable for SleepFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Some(duration) = arguments[0]
+                .as_number()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+            else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must be a non-negative number.".into(),
+                });
+            };
+
+            thread::sleep(duration);
+
+            Ok(Some(Value::Nil))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "sleep")
+        }
+    }
+
+    /// Print a value like the `print` statement, but without the trailing newline.
+    #[derive(Debug, PartialEq)]
+    pub struct WriteFunction {}
+
+    impl super::Callable for WriteFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
             token: Token,
         ) -> ExecuteInterpreterResult {
-            match SystemTime::now().duration_since(UNIX_EPOCH) {
-                Ok(duration) => Ok(Some(Value::Number(duration.as_secs() as f64))),
+            print!("{}", arguments[0]);
+
+            match io::stdout().flush() {
+                Ok(()) => Ok(Some(Value::Nil)),
                 Err(error) => Err(InterpreterError {
                     token: Some(token),
-                    message: format!("SystemTime error: {}", error),
+                    message: format!("IO error: {}", error),
                 }),
             }
         }
 
         fn as_str(&self) -> String {
-            format!("<native fn {}>", "clock")
+            format!("<native fn {}>", "write")
+        }
+    }
+
+    /// Print a value like the `print` statement, but to the standard error.
+    #[derive(Debug, PartialEq)]
+    pub struct EprintFunction {}
+
+    impl super::Callable for EprintFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            eprintln!("{}", arguments[0]);
+
+            Ok(Some(Value::Nil))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "eprint")
+        }
+    }
+
+    /// Raise a runtime error when the condition is not truthy.
+    #[derive(Debug, PartialEq)]
+    pub struct AssertFunction {}
+
+    impl super::Callable for AssertFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let condition = arguments.into_iter().next().unwrap_or(Value::Nil);
+
+            if interpreter.is_truthy(&condition) {
+                Ok(Some(Value::Nil))
+            } else {
+                Err(InterpreterError {
+                    token: Some(token),
+                    message: "Assertion failed.".into(),
+                })
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "assert")
+        }
+    }
+
+    /// Raise a runtime error when both values are not equal, showing them in the message.
+    #[derive(Debug, PartialEq)]
+    pub struct AssertEqFunction {}
+
+    impl super::Callable for AssertEqFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=2
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let (actual, expected) = (&arguments[0], &arguments[1]);
+
+            if actual == expected {
+                Ok(Some(Value::Nil))
+            } else {
+                Err(InterpreterError {
+                    token: Some(token),
+                    message: format!("Assertion failed: expected {expected} but got {actual}."),
+                })
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "assert_eq")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct TypeFunction {}
+
+    impl super::Callable for TypeFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::String(Rc::new(
+                arguments[0].type_name().into(),
+            ))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "type")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct IsNilFunction {}
+
+    impl super::Callable for IsNilFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::Boolean(matches!(arguments[0], Value::Nil))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "is_nil")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct IsNumberFunction {}
+
+    impl super::Callable for IsNumberFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::Boolean(matches!(
+                arguments[0],
+                Value::Number(_) | Value::Integer(_)
+            ))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "is_number")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct IsStringFunction {}
+
+    impl super::Callable for IsStringFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::Boolean(matches!(
+                arguments[0],
+                Value::String(_)
+            ))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "is_string")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct IsCallableFunction {}
+
+    impl super::Callable for IsCallableFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            _: Token,
+        ) -> ExecuteInterpreterResult {
+            Ok(Some(Value::Boolean(matches!(
+                arguments[0],
+                Value::Function(_)
+            ))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "is_callable")
+        }
+    }
+
+    /// The Unicode code point of the first character of a string.
+    #[derive(Debug, PartialEq)]
+    pub struct OrdFunction {}
+
+    impl super::Callable for OrdFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Value::String(value) = &arguments[0] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must be a string.".into(),
+                });
+            };
+
+            match value.chars().next() {
+                Some(character) => Ok(Some(Value::Integer(character as i64))),
+                None => Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must not be an empty string.".into(),
+                }),
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "ord")
+        }
+    }
+
+    /// A single character string from a Unicode code point.
+    #[derive(Debug, PartialEq)]
+    pub struct ChrFunction {}
+
+    impl super::Callable for ChrFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Some(code_point) = arguments[0].as_integer() else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Argument must be an integer.".into(),
+                });
+            };
+
+            match u32::try_from(code_point).ok().and_then(char::from_u32) {
+                Some(character) => Ok(Some(Value::String(Rc::new(character.into())))),
+                None => Err(InterpreterError {
+                    token: Some(token),
+                    message: format!("{code_point} is not a valid code point."),
+                }),
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "chr")
+        }
+    }
+
+    /// Up to `length` characters of a string from the `start` character, clamped to the string's end.
+    #[derive(Debug, PartialEq)]
+    pub struct SubstrFunction {}
+
+    impl super::Callable for SubstrFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            3..=3
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Value::String(value) = &arguments[0] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "First argument must be a string.".into(),
+                });
+            };
+
+            let (Some(start), Some(length)) =
+                (arguments[1].as_integer(), arguments[2].as_integer())
+            else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Start and length must be integers.".into(),
+                });
+            };
+
+            if start < 0 || length < 0 {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Start and length must not be negative.".into(),
+                });
+            }
+
+            let output: String = value
+                .chars()
+                .skip(start as usize)
+                .take(length as usize)
+                .collect();
+
+            Ok(Some(Value::String(Rc::new(output))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "substr")
+        }
+    }
+
+    /// The elements of a list, as printed, with a separator in between.
+    #[derive(Debug, PartialEq)]
+    pub struct JoinFunction {}
+
+    impl super::Callable for JoinFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=2
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Value::List(elements) = &arguments[0] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "First argument must be a list.".into(),
+                });
+            };
+
+            let Value::String(separator) = &arguments[1] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Separator must be a string.".into(),
+                });
+            };
+
+            let parts: Vec<String> = elements.borrow().iter().map(ToString::to_string).collect();
+
+            Ok(Some(Value::String(Rc::new(parts.join(separator)))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "join")
+        }
+    }
+
+    /// The parts of a string between each separator, or its characters when the separator is empty.
+    /// An empty string gives a single empty part.
+    #[derive(Debug, PartialEq)]
+    pub struct SplitFunction {}
+
+    impl super::Callable for SplitFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=2
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let (Value::String(value), Value::String(separator)) = (&arguments[0], &arguments[1])
+            else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Arguments must be strings.".into(),
+                });
+            };
+
+            let parts: Vec<String> = if value.is_empty() {
+                vec![String::new()]
+            } else if separator.is_empty() {
+                value.chars().map(String::from).collect()
+            } else {
+                value.split(separator.as_str()).map(String::from).collect()
+            };
+
+            let parts = parts
+                .into_iter()
+                .map(|part| Value::String(Rc::new(part)))
+                .collect();
+
+            Ok(Some(Value::List(Rc::new(RefCell::new(parts)))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "split")
+        }
+    }
+
+    /// The integers from `start` included to `end` excluded, going by `step`.
+    /// Called as `range(end)`, `range(start, end)` or `range(start, end, step)`.
+    #[derive(Debug, PartialEq)]
+    pub struct RangeFunction {}
+
+    impl super::Callable for RangeFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=3
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let Some(bounds) = arguments
+                .iter()
+                .map(Value::as_integer)
+                .collect::<Option<Vec<i64>>>()
+            else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Arguments must be integers.".into(),
+                });
+            };
+
+            let (start, end, step) = match bounds[..] {
+                [end] => (0, end, 1),
+                [start, end] => (start, end, 1),
+                [start, end, step] => (start, end, step),
+                _ => unreachable!("arity is checked before calling"),
+            };
+
+            if step == 0 {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Step must not be zero.".into(),
+                });
+            }
+
+            let mut values = Vec::new();
+            let mut current = start;
+
+            while (step > 0 && current < end) || (step < 0 && current > end) {
+                values.push(Value::Integer(current));
+
+                match current.checked_add(step) {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+
+            Ok(Some(Value::List(Rc::new(RefCell::new(values)))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "range")
+        }
+    }
+
+    /// Replace each `{}` of a template with the next argument, as printed.
+    /// `{{` and `}}` stand for literal braces.
+    #[derive(Debug, PartialEq)]
+    pub struct FormatFunction {}
+
+    impl super::Callable for FormatFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=usize::MAX
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let error = |message: String| {
+                Err(InterpreterError {
+                    token: Some(token.clone()),
+                    message,
+                })
+            };
+
+            let Value::String(template) = &arguments[0] else {
+                return error("Template must be a string.".into());
+            };
+
+            let values = &arguments[1..];
+            let mut output = String::with_capacity(template.len());
+            let mut placeholders = 0;
+            let mut characters = template.chars().peekable();
+
+            while let Some(character) = characters.next() {
+                match (character, characters.peek()) {
+                    ('{', Some('{')) | ('}', Some('}')) => {
+                        characters.next();
+                        output.push(character);
+                    }
+                    ('{', Some('}')) => {
+                        characters.next();
+
+                        if let Some(value) = values.get(placeholders) {
+                            output.push_str(&value.to_string());
+                        }
+
+                        placeholders += 1;
+                    }
+                    ('{' | '}', _) => {
+                        return error(format!("Unmatched '{character}' in template."));
+                    }
+                    _ => output.push(character),
+                }
+            }
+
+            if placeholders != values.len() {
+                return error(format!(
+                    "Template has {placeholders} placeholders but got {} arguments.",
+                    values.len()
+                ));
+            }
+
+            Ok(Some(Value::String(Rc::new(output))))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "format")
+        }
+    }
+
+    /// The path argument of the file natives, once file access is known to be allowed.
+    fn file_path<'a>(
+        interpreter: &Interpreter,
+        argument: &'a Value,
+        token: &Token,
+    ) -> Result<&'a str, InterpreterError> {
+        if !interpreter.file_access {
+            return Err(InterpreterError {
+                token: Some(token.clone()),
+                message: "File access is disabled.".into(),
+            });
+        }
+
+        match argument {
+            Value::String(path) => Ok(path),
+            _ => Err(InterpreterError {
+                token: Some(token.clone()),
+                message: "Path must be a string.".into(),
+            }),
+        }
+    }
+
+    /// The whole content of a file, as a string.
+    #[derive(Debug, PartialEq)]
+    pub struct ReadFileFunction {}
+
+    impl super::Callable for ReadFileFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            1..=1
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let path = file_path(interpreter, &arguments[0], &token)?;
+
+            match fs::read_to_string(path) {
+                Ok(content) => Ok(Some(Value::String(Rc::new(content)))),
+                Err(error) => Err(InterpreterError {
+                    token: Some(token),
+                    message: format!("Could not read file '{path}': {error}"),
+                }),
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "read_file")
+        }
+    }
+
+    /// Replace the content of a file with a string, creating the file if needed.
+    #[derive(Debug, PartialEq)]
+    pub struct WriteFileFunction {}
+
+    impl super::Callable for WriteFileFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=2
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            let path = file_path(interpreter, &arguments[0], &token)?;
+
+            let Value::String(content) = &arguments[1] else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Content must be a string.".into(),
+                });
+            };
+
+            match fs::write(path, content.as_bytes()) {
+                Ok(()) => Ok(Some(Value::Nil)),
+                Err(error) => Err(InterpreterError {
+                    token: Some(token),
+                    message: format!("Could not write file '{path}': {error}"),
+                }),
+            }
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "write_file")
+        }
+    }
+
+    /// The extreme of two or more numbers, as picked by `keep`, returned as given.
+    fn extremum(
+        arguments: Vec<Value>,
+        token: Token,
+        keep: fn(f64, f64) -> bool,
+    ) -> ExecuteInterpreterResult {
+        let mut arguments = arguments.into_iter();
+        let mut best = arguments.next().unwrap_or(Value::Nil);
+
+        for value in arguments {
+            let (Some(x), Some(y)) = (value.as_number(), best.as_number()) else {
+                return Err(InterpreterError {
+                    token: Some(token),
+                    message: "Arguments must be numbers.".into(),
+                });
+            };
+
+            if keep(x, y) {
+                best = value;
+            }
+        }
+
+        Ok(Some(best))
+    }
+
+    /// The smallest of two or more numbers.
+    #[derive(Debug, PartialEq)]
+    pub struct MinFunction {}
+
+    impl super::Callable for MinFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=usize::MAX
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            extremum(arguments, token, |x, smallest| x < smallest)
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "min")
+        }
+    }
+
+    /// The largest of two or more numbers.
+    #[derive(Debug, PartialEq)]
+    pub struct MaxFunction {}
+
+    impl super::Callable for MaxFunction {
+        fn arity(&self) -> RangeInclusive<usize> {
+            2..=usize::MAX
+        }
+
+        fn call(
+            &self,
+            _: &mut Interpreter,
+            arguments: Vec<Value>,
+            token: Token,
+        ) -> ExecuteInterpreterResult {
+            extremum(arguments, token, |x, largest| x > largest)
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", "max")
+        }
+    }
+
+    /// A built-in method bound to a string receiver, e.g. `"hello".upper`.
+    ///
+    /// Available methods, all taking no argument:
+    /// - `upper()` returns the string in upper case
+    /// - `lower()` returns the string in lower case
+    /// - `length()` returns the number of characters
+    #[derive(Debug, PartialEq)]
+    pub struct StringMethod {
+        receiver: Rc<String>,
+        name: &'static str,
+    }
+
+    impl StringMethod {
+        const NAMES: [&'static str; 3] = ["upper", "lower", "length"];
+
+        pub fn bind(receiver: Rc<String>, name: &str) -> Option<Self> {
+            Self::NAMES
+                .iter()
+                .find(|candidate| **candidate == name)
+                .map(|name| StringMethod { receiver, name })
+        }
+    }
+
+    impl super::Callable for StringMethod {
+        fn arity(&self) -> RangeInclusive<usize> {
+            0..=0
+        }
+
+        fn call(&self, _: &mut Interpreter, _: Vec<Value>, _: Token) -> ExecuteInterpreterResult {
+            let value = match self.name {
+                "upper" => Value::String(Rc::new(self.receiver.to_uppercase())),
+                "lower" => Value::String(Rc::new(self.receiver.to_lowercase())),
+                "length" => Value::Integer(self.receiver.chars().count() as i64),
+                _ => unreachable!(),
+            };
+
+            Ok(Some(value))
+        }
+
+        fn as_str(&self) -> String {
+            format!("<native fn {}>", self.name)
         }
     }
 }
@@ -1,4 +1,7 @@
-use std::{fmt, rc::Rc};
+use {
+    crate::format_number,
+    std::{fmt, rc::Rc},
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
@@ -7,10 +10,16 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
 
+    Ampersand,
+    Caret,
+    Colon,
     Comma,
     Dot,
     Minus,
+    Pipe,
     Plus,
     Semicolon,
     Slash,
@@ -23,8 +32,13 @@ pub enum TokenType {
     EqualEqual,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     Less,
     LessEqual,
+    LessLess,
+    MinusMinus,
+    PlusPlus,
+    StarStar,
 
     // Literals.
     Identifier,
@@ -33,21 +47,31 @@ pub enum TokenType {
 
     // Keywords.
     And,
+    Case,
+    Catch,
     Class,
+    Const,
+    Default,
+    Do,
     Else,
     False,
     Fun,
     For,
     If,
+    In,
     Nil,
     Or,
     Print,
     Return,
     Super,
+    Switch,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
+    Xor,
 
     // Other.
     Eof,
@@ -60,9 +84,15 @@ impl fmt::Display for TokenType {
             TokenType::RightParen => write!(f, "RIGHT_PAREN"),
             TokenType::LeftBrace => write!(f, "LEFT_BRACE"),
             TokenType::RightBrace => write!(f, "RIGHT_BRACE"),
+            TokenType::LeftBracket => write!(f, "LEFT_BRACKET"),
+            TokenType::RightBracket => write!(f, "RIGHT_BRACKET"),
+            TokenType::Ampersand => write!(f, "AMPERSAND"),
+            TokenType::Caret => write!(f, "CARET"),
+            TokenType::Colon => write!(f, "COLON"),
             TokenType::Comma => write!(f, "COMMA"),
             TokenType::Dot => write!(f, "DOT"),
             TokenType::Minus => write!(f, "MINUS"),
+            TokenType::Pipe => write!(f, "PIPE"),
             TokenType::Plus => write!(f, "PLUS"),
             TokenType::Semicolon => write!(f, "SEMICOLON"),
             TokenType::Slash => write!(f, "SLASH"),
@@ -73,27 +103,42 @@ impl fmt::Display for TokenType {
             TokenType::EqualEqual => write!(f, "EQUAL_EQUAL"),
             TokenType::Greater => write!(f, "GREATER"),
             TokenType::GreaterEqual => write!(f, "GREATER_EQUAL"),
+            TokenType::GreaterGreater => write!(f, "GREATER_GREATER"),
             TokenType::Less => write!(f, "LESS"),
             TokenType::LessEqual => write!(f, "LESS_EQUAL"),
+            TokenType::LessLess => write!(f, "LESS_LESS"),
+            TokenType::MinusMinus => write!(f, "MINUS_MINUS"),
+            TokenType::PlusPlus => write!(f, "PLUS_PLUS"),
+            TokenType::StarStar => write!(f, "STAR_STAR"),
             TokenType::Identifier => write!(f, "IDENTIFIER"),
             TokenType::String => write!(f, "STRING"),
             TokenType::Number => write!(f, "NUMBER"),
             TokenType::And => write!(f, "AND"),
+            TokenType::Case => write!(f, "CASE"),
+            TokenType::Catch => write!(f, "CATCH"),
             TokenType::Class => write!(f, "CLASS"),
+            TokenType::Const => write!(f, "CONST"),
+            TokenType::Default => write!(f, "DEFAULT"),
+            TokenType::Do => write!(f, "DO"),
             TokenType::Else => write!(f, "ELSE"),
             TokenType::False => write!(f, "FALSE"),
             TokenType::Fun => write!(f, "FUN"),
             TokenType::For => write!(f, "FOR"),
             TokenType::If => write!(f, "IF"),
+            TokenType::In => write!(f, "IN"),
             TokenType::Nil => write!(f, "NIL"),
             TokenType::Or => write!(f, "OR"),
             TokenType::Print => write!(f, "PRINT"),
             TokenType::Return => write!(f, "RETURN"),
             TokenType::Super => write!(f, "SUPER"),
+            TokenType::Switch => write!(f, "SWITCH"),
             TokenType::This => write!(f, "THIS"),
+            TokenType::Throw => write!(f, "THROW"),
             TokenType::True => write!(f, "TRUE"),
+            TokenType::Try => write!(f, "TRY"),
             TokenType::Var => write!(f, "VAR"),
             TokenType::While => write!(f, "WHILE"),
+            TokenType::Xor => write!(f, "XOR"),
             TokenType::Eof => write!(f, "EOF"),
         }
     }
@@ -105,6 +150,10 @@ pub struct Token {
     pub literal: Option<Literal>,
     pub lexeme: String,
     pub line: usize,
+    /// Offset of the first character of the token in the source, counted in characters.
+    pub start: usize,
+    /// Offset right after the last character of the token, counted in characters.
+    pub end: usize,
 }
 
 impl Token {
@@ -113,14 +162,38 @@ impl Token {
         lexeme: String,
         literal: Option<Literal>,
         line: usize,
+        start: usize,
+        end: usize,
     ) -> Self {
         Token {
             token_type,
             literal,
             lexeme,
             line,
+            start,
+            end,
         }
     }
+
+    pub fn token_type(&self) -> &TokenType {
+        &self.token_type
+    }
+
+    pub fn lexeme(&self) -> &str {
+        &self.lexeme
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
 }
 
 impl fmt::Display for Token {
@@ -138,6 +211,7 @@ pub enum Literal {
     Boolean(bool),
     String(Rc<String>),
     Number(f64),
+    Integer(i64),
 }
 
 impl fmt::Display for Literal {
@@ -152,14 +226,9 @@ impl fmt::Display for Literal {
                 }
             }
             Literal::String(value) => write!(f, "{value}"),
-            Literal::Number(value) => {
-                let int = *value as i64;
-                if value.fract() == 0.0 {
-                    write!(f, "{int}.0")
-                } else {
-                    write!(f, "{value}")
-                }
-            }
+            // tokens and syntax trees always show a fraction, e.g. `42.0`, to tell numbers apart
+            Literal::Integer(value) => write!(f, "{}", format_number(value, true)),
+            Literal::Number(value) => write!(f, "{}", format_number(value, true)),
         }
     }
 }
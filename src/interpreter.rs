@@ -1,6 +1,12 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, fmt, rc::Rc};
 
-use crate::{native, Environment, Expression, LoxFunction, Statement, Token, TokenType, Value};
+use crate::{
+    native, Environment, Expression, HashableValue, LoxFunction, OrderedMap, ParseError, Parser,
+    Scanner, Statement, Token, TokenType, Value,
+};
+
+/// Longest string, in bytes, a repetition may build before failing instead of exhausting the memory.
+const MAX_REPEAT_LENGTH: usize = 1 << 28;
 
 #[derive(Debug, thiserror::Error)]
 #[error("{message}")]
@@ -9,13 +15,84 @@ pub struct InterpreterError {
     pub message: String,
 }
 
+/// Any error from going through the whole pipeline, from scanning the source to evaluating it.
+#[derive(Debug, thiserror::Error)]
+pub enum LoxError {
+    /// The scanner already reported its errors on stderr.
+    #[error("Could not scan the source.")]
+    Scan,
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Runtime(#[from] InterpreterError),
+}
+
+/// A call that was in progress when a runtime error happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    /// The called function, as printed, e.g. `<fn fib>`.
+    pub function: String,
+    /// Line of the call site.
+    pub line: usize,
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in {} called at [line {}]", self.function, self.line)
+    }
+}
+
 pub type ExecuteInterpreterResult = Result<Option<Value>, InterpreterError>;
 pub type EvaluateInterpreterResult = Result<Value, InterpreterError>;
 
-#[derive(Debug)]
 pub struct Interpreter {
     pub globals: Environment,
     pub environment: Environment,
+    pub call_depth: usize,
+    pub max_call_depth: usize,
+    /// When set, `+` with exactly one string operand stringifies the other one instead of failing.
+    /// Off by default to stay with strict Lox.
+    pub string_coercion: bool,
+    /// Iterations a single `while`, `for` or `do` loop may run before failing.
+    /// Every loop gets the whole budget, counted from its start.
+    /// `None`, the default, doesn't limit them.
+    pub max_loop_iterations: Option<usize>,
+    /// Force `color` to emit (`Some(true)`) or skip (`Some(false)`) ANSI escapes.
+    /// `None` emits them only when stdout is a terminal.
+    pub colors: Option<bool>,
+    /// Allow the file natives (`read_file`, `write_file`) to touch the file system.
+    /// On by default, turn it off to sandbox untrusted scripts.
+    pub file_access: bool,
+    /// Time source of the `clock` native, in seconds.
+    /// Replace it to freeze or fake the time, e.g. in tests.
+    pub clock: Box<dyn Fn() -> f64>,
+    /// The value of a `throw` while its error unwinds, so `catch` can tell it from the interpreter's own errors.
+    thrown: Option<Value>,
+    /// Calls unwound by the last runtime error, innermost first.
+    pub stack_trace: Vec<StackFrame>,
+}
+
+impl fmt::Debug for Interpreter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interpreter")
+            .field("globals", &self.globals)
+            .field("environment", &self.environment)
+            .field("call_depth", &self.call_depth)
+            .field("max_call_depth", &self.max_call_depth)
+            .field("string_coercion", &self.string_coercion)
+            .field("max_loop_iterations", &self.max_loop_iterations)
+            .field("colors", &self.colors)
+            .field("file_access", &self.file_access)
+            .field("thrown", &self.thrown)
+            .field("stack_trace", &self.stack_trace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
@@ -26,19 +103,185 @@ impl Interpreter {
             "clock".into(),
             Value::Function(Rc::new(RefCell::new(native::ClockFunction {}))),
         );
+        environment.define(
+            "is_integer".into(),
+            Value::Function(Rc::new(RefCell::new(native::IsIntegerFunction {}))),
+        );
+        environment.define(
+            "as_integer".into(),
+            Value::Function(Rc::new(RefCell::new(native::AsIntegerFunction {}))),
+        );
+        environment.define(
+            "to_string".into(),
+            Value::Function(Rc::new(RefCell::new(native::ToStringFunction {}))),
+        );
+        environment.define(
+            "to_number".into(),
+            Value::Function(Rc::new(RefCell::new(native::ToNumberFunction {}))),
+        );
+        environment.define(
+            "parse_number".into(),
+            Value::Function(Rc::new(RefCell::new(native::ParseNumberFunction {}))),
+        );
+        environment.define(
+            "color".into(),
+            Value::Function(Rc::new(RefCell::new(native::ColorFunction {}))),
+        );
+        environment.define(
+            "sleep".into(),
+            Value::Function(Rc::new(RefCell::new(native::SleepFunction {}))),
+        );
+        environment.define(
+            "write".into(),
+            Value::Function(Rc::new(RefCell::new(native::WriteFunction {}))),
+        );
+        environment.define(
+            "eprint".into(),
+            Value::Function(Rc::new(RefCell::new(native::EprintFunction {}))),
+        );
+        environment.define(
+            "assert".into(),
+            Value::Function(Rc::new(RefCell::new(native::AssertFunction {}))),
+        );
+        environment.define(
+            "assert_eq".into(),
+            Value::Function(Rc::new(RefCell::new(native::AssertEqFunction {}))),
+        );
+        environment.define(
+            "type".into(),
+            Value::Function(Rc::new(RefCell::new(native::TypeFunction {}))),
+        );
+        environment.define(
+            "is_nil".into(),
+            Value::Function(Rc::new(RefCell::new(native::IsNilFunction {}))),
+        );
+        environment.define(
+            "is_number".into(),
+            Value::Function(Rc::new(RefCell::new(native::IsNumberFunction {}))),
+        );
+        environment.define(
+            "is_string".into(),
+            Value::Function(Rc::new(RefCell::new(native::IsStringFunction {}))),
+        );
+        environment.define(
+            "is_callable".into(),
+            Value::Function(Rc::new(RefCell::new(native::IsCallableFunction {}))),
+        );
+        environment.define(
+            "ord".into(),
+            Value::Function(Rc::new(RefCell::new(native::OrdFunction {}))),
+        );
+        environment.define(
+            "chr".into(),
+            Value::Function(Rc::new(RefCell::new(native::ChrFunction {}))),
+        );
+        environment.define(
+            "substr".into(),
+            Value::Function(Rc::new(RefCell::new(native::SubstrFunction {}))),
+        );
+        environment.define(
+            "read_file".into(),
+            Value::Function(Rc::new(RefCell::new(native::ReadFileFunction {}))),
+        );
+        environment.define(
+            "write_file".into(),
+            Value::Function(Rc::new(RefCell::new(native::WriteFileFunction {}))),
+        );
+        environment.define(
+            "min".into(),
+            Value::Function(Rc::new(RefCell::new(native::MinFunction {}))),
+        );
+        environment.define(
+            "max".into(),
+            Value::Function(Rc::new(RefCell::new(native::MaxFunction {}))),
+        );
+        environment.define(
+            "join".into(),
+            Value::Function(Rc::new(RefCell::new(native::JoinFunction {}))),
+        );
+        environment.define(
+            "split".into(),
+            Value::Function(Rc::new(RefCell::new(native::SplitFunction {}))),
+        );
+        environment.define(
+            "range".into(),
+            Value::Function(Rc::new(RefCell::new(native::RangeFunction {}))),
+        );
+        environment.define(
+            "format".into(),
+            Value::Function(Rc::new(RefCell::new(native::FormatFunction {}))),
+        );
 
         Interpreter {
             globals: environment.clone(),
             environment,
+            call_depth: 0,
+            max_call_depth: 1000,
+            string_coercion: false,
+            max_loop_iterations: None,
+            colors: None,
+            file_access: true,
+            clock: Box::new(native::system_clock),
+            thrown: None,
+            stack_trace: Vec::new(),
         }
     }
 
     pub fn interpret(&mut self, statements: Vec<Statement>) -> ExecuteInterpreterResult {
+        self.run_statements(statements)?;
+
+        Ok(None)
+    }
+
+    /// Register a native function in the global scope, for embedders extending the language.
+    /// The function is only called with exactly `arity` arguments.
+    pub fn define_native(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, Vec<Value>, &Token) -> ExecuteInterpreterResult + 'static,
+    ) {
+        let native = native::HostNativeFunction {
+            name: name.into(),
+            arity,
+            function: Box::new(function),
+        };
+
+        self.globals
+            .define(name.into(), Value::Function(Rc::new(RefCell::new(native))));
+    }
+
+    /// Scan, parse and evaluate a single expression, e.g. `interpreter.eval_str("1 + 2")`.
+    pub fn eval_str(&mut self, source: &str) -> Result<Value, LoxError> {
+        let mut scanner = Scanner::new(source.into());
+        let tokens = scanner.scan_tokens();
+
+        if scanner.had_error {
+            return Err(LoxError::Scan);
+        }
+
+        let expression = Parser::new(tokens).parse_expression()?;
+
+        self.stack_trace.clear();
+        Ok(self.evaluate(expression)?)
+    }
+
+    /// Run the statements and return the value of the last expression statement, or `nil` if there was none.
+    /// Useful for a REPL that wants to echo `1 + 1`.
+    pub fn run_statements(&mut self, statements: Vec<Statement>) -> EvaluateInterpreterResult {
+        let mut last = Value::Nil;
+        self.stack_trace.clear();
+
         for statement in statements {
-            self.execute(statement)?;
+            match statement {
+                Statement::Expression(expression) => last = self.evaluate(expression)?,
+                statement => {
+                    self.execute(statement)?;
+                }
+            }
         }
 
-        Ok(None)
+        Ok(last)
     }
 
     pub fn execute(&mut self, statement: Statement) -> ExecuteInterpreterResult {
@@ -74,7 +317,7 @@ impl Interpreter {
             } => {
                 let result = self.evaluate(condition)?;
 
-                if self.is_truthy(result) {
+                if self.is_truthy(&result) {
                     Ok(self.execute(*then_branch)?)
                 } else if let Some(statement) = else_branch {
                     Ok(self.execute(*statement)?)
@@ -83,10 +326,7 @@ impl Interpreter {
                 }
             }
             Statement::Print(expression) => {
-                match self.evaluate(expression)? {
-                    Value::Number(value) => println!("{value}"),
-                    value => println!("{value}"),
-                }
+                println!("{}", self.evaluate(expression)?);
 
                 Ok(None)
             }
@@ -100,6 +340,12 @@ impl Interpreter {
 
                 Ok(None)
             }
+            Statement::Constant { name, initializer } => {
+                let value = self.evaluate(initializer)?;
+                self.environment.define_constant(name.lexeme, value);
+
+                Ok(None)
+            }
             Statement::Return { keyword: _, value } => {
                 if let Some(expression) = value {
                     return Ok(Some(self.evaluate(expression)?));
@@ -107,21 +353,141 @@ impl Interpreter {
 
                 Ok(Some(Value::Nil))
             }
-            Statement::While { condition, body } => {
+            Statement::While {
+                keyword,
+                condition,
+                body,
+                increment,
+            } => {
+                let mut iterations = 0;
+
                 loop {
                     let is_true = self.evaluate(condition.clone())?;
 
-                    if !self.is_truthy(is_true) {
+                    if !self.is_truthy(&is_true) {
                         break;
                     }
 
+                    self.count_iteration(&keyword, &mut iterations)?;
+
                     if let Some(returned) = self.execute(*body.clone())? {
                         return Ok(Some(returned));
                     }
+
+                    if let Some(increment) = &increment {
+                        self.evaluate(increment.clone())?;
+                    }
+                }
+
+                Ok(None)
+            }
+            Statement::DoWhile {
+                keyword,
+                body,
+                condition,
+            } => {
+                let mut iterations = 0;
+
+                loop {
+                    self.count_iteration(&keyword, &mut iterations)?;
+
+                    if let Some(returned) = self.execute(*body.clone())? {
+                        return Ok(Some(returned));
+                    }
+
+                    let is_true = self.evaluate(condition.clone())?;
+
+                    if !self.is_truthy(&is_true) {
+                        break;
+                    }
+                }
+
+                Ok(None)
+            }
+            Statement::ForIn {
+                name,
+                keyword,
+                iterable,
+                body,
+            } => {
+                let items: Vec<Value> = match self.evaluate(iterable)? {
+                    Value::String(string) => string
+                        .chars()
+                        .map(|character| Value::String(Rc::new(character.to_string())))
+                        .collect(),
+                    Value::List(elements) => elements.borrow().clone(),
+                    Value::Map(map) => map.borrow().keys().map(Value::from).collect(),
+                    _ => {
+                        return Err(InterpreterError {
+                            token: Some(keyword),
+                            message: "Can only iterate over strings, lists and maps.".into(),
+                        })
+                    }
+                };
+
+                for item in items {
+                    let mut environment = self.environment.enclose();
+                    environment.define(name.lexeme.clone(), item);
+
+                    if let Some(returned) = self.execute_block(vec![*body.clone()], environment)? {
+                        return Ok(Some(returned));
+                    }
                 }
 
                 Ok(None)
             }
+            Statement::Switch {
+                discriminant,
+                cases,
+                default,
+            } => {
+                let value = self.evaluate(discriminant)?;
+
+                for (case, body) in cases {
+                    if self.evaluate(case)? == value {
+                        return self.execute_block(body, self.environment.enclose());
+                    }
+                }
+
+                match default {
+                    Some(body) => self.execute_block(body, self.environment.enclose()),
+                    None => Ok(None),
+                }
+            }
+            Statement::Throw { keyword, value } => {
+                let value = self.evaluate(value)?;
+                let message = format!("Uncaught exception: {value}");
+
+                self.thrown = Some(value);
+
+                Err(InterpreterError {
+                    token: Some(keyword),
+                    message,
+                })
+            }
+            Statement::Try {
+                body,
+                name,
+                handler,
+            } => {
+                // nothing can be unwinding when a try starts, a leftover is from a throw nobody caught
+                self.thrown = None;
+
+                match self.execute_block(body, self.environment.enclose()) {
+                    Err(error) => match self.thrown.take() {
+                        Some(value) => {
+                            self.stack_trace.clear();
+
+                            let mut environment = self.environment.enclose();
+                            environment.define(name.lexeme, value);
+
+                            self.execute_block(handler, environment)
+                        }
+                        None => Err(error),
+                    },
+                    result => result,
+                }
+            }
             Statement::Block(statements) => {
                 Ok(self.execute_block(statements, self.environment.enclose())?)
             }
@@ -162,10 +528,16 @@ impl Interpreter {
                 let right_child = self.evaluate(*right)?;
 
                 match operator.token_type {
-                    TokenType::Bang => Ok(Value::Boolean(!self.is_truthy(right_child))),
-                    TokenType::Minus => Ok(Value::Number(
-                        -self.check_number_operand(&operator, &right_child)?,
-                    )),
+                    TokenType::Bang => Ok(Value::Boolean(!self.is_truthy(&right_child))),
+                    // -0 has no integer representation, so it goes through floats like overflowing values
+                    TokenType::Minus => match right_child {
+                        Value::Integer(value) if value != 0 && value != i64::MIN => {
+                            Ok(Value::Integer(-value))
+                        }
+                        _ => Ok(Value::Number(
+                            -self.check_number_operand(&operator, &right_child)?,
+                        )),
+                    },
                     _ => panic!("unreachable"),
                 }
             }
@@ -179,26 +551,73 @@ impl Interpreter {
 
                 match operator.token_type {
                     TokenType::Slash => {
-                        let (x, y) =
+                        let (_, y) =
                             self.check_number_operands(&operator, &left_child, &right_child)?;
 
-                        return Ok(Value::Number(x / y));
+                        if y == 0.0 {
+                            return Err(InterpreterError {
+                                token: Some(operator.clone()),
+                                message: "Division by zero.".into(),
+                            });
+                        }
+
+                        self.arithmetic(
+                            &operator,
+                            &left_child,
+                            &right_child,
+                            |a, b| {
+                                a.checked_rem(b)
+                                    .filter(|rest| *rest == 0)
+                                    .and(a.checked_div(b))
+                            },
+                            |x, y| x / y,
+                        )
                     }
                     TokenType::Star => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        let repeated = match (&left_child, &right_child) {
+                            (Value::String(text), count) | (count, Value::String(text))
+                                if count.as_number().is_some() =>
+                            {
+                                Some((text, count))
+                            }
+                            _ => None,
+                        };
 
-                        return Ok(Value::Number(x * y));
-                    }
-                    TokenType::Minus => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        if let Some((text, count)) = repeated {
+                            return self.repeat(&operator, text, count);
+                        }
 
-                        return Ok(Value::Number(x - y));
+                        self.arithmetic(
+                            &operator,
+                            &left_child,
+                            &right_child,
+                            i64::checked_mul,
+                            |x, y| x * y,
+                        )
                     }
+                    TokenType::StarStar => self.arithmetic(
+                        &operator,
+                        &left_child,
+                        &right_child,
+                        |a, b| u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+                        f64::powf,
+                    ),
+                    TokenType::Minus => self.arithmetic(
+                        &operator,
+                        &left_child,
+                        &right_child,
+                        i64::checked_sub,
+                        |x, y| x - y,
+                    ),
                     TokenType::Plus => {
-                        if let (Value::Number(a), Value::Number(b)) = (&left_child, &right_child) {
-                            return Ok(Value::Number(*a + *b));
+                        if left_child.as_number().is_some() && right_child.as_number().is_some() {
+                            return self.arithmetic(
+                                &operator,
+                                &left_child,
+                                &right_child,
+                                i64::checked_add,
+                                |x, y| x + y,
+                            );
                         }
 
                         if let (Value::String(a), Value::String(b)) = (&left_child, &right_child) {
@@ -208,49 +627,90 @@ impl Interpreter {
                             return Ok(Value::String(Rc::new(output)));
                         }
 
+                        if self.string_coercion
+                            && (matches!(left_child, Value::String(_))
+                                || matches!(right_child, Value::String(_)))
+                        {
+                            return Ok(Value::String(Rc::new(format!(
+                                "{left_child}{right_child}"
+                            ))));
+                        }
+
                         Err(InterpreterError {
                             token: Some(operator.clone()),
                             message: "Operands must be two numbers or two strings.".into(),
                         })
                     }
                     TokenType::Greater => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        let ordering = self.compare(&operator, &left_child, &right_child)?;
 
-                        return Ok(Value::Boolean(x > y));
+                        Ok(Value::Boolean(ordering.is_some_and(Ordering::is_gt)))
                     }
                     TokenType::GreaterEqual => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        let ordering = self.compare(&operator, &left_child, &right_child)?;
 
-                        return Ok(Value::Boolean(x >= y));
+                        Ok(Value::Boolean(ordering.is_some_and(Ordering::is_ge)))
                     }
                     TokenType::Less => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        let ordering = self.compare(&operator, &left_child, &right_child)?;
 
-                        return Ok(Value::Boolean(x < y));
+                        Ok(Value::Boolean(ordering.is_some_and(Ordering::is_lt)))
                     }
                     TokenType::LessEqual => {
-                        let (x, y) =
-                            self.check_number_operands(&operator, &left_child, &right_child)?;
+                        let ordering = self.compare(&operator, &left_child, &right_child)?;
+
+                        Ok(Value::Boolean(ordering.is_some_and(Ordering::is_le)))
+                    }
+                    TokenType::Ampersand => {
+                        let (a, b) =
+                            self.check_integer_operands(&operator, &left_child, &right_child)?;
+
+                        Ok(Value::Integer(a & b))
+                    }
+                    TokenType::Pipe => {
+                        let (a, b) =
+                            self.check_integer_operands(&operator, &left_child, &right_child)?;
 
-                        return Ok(Value::Boolean(x <= y));
+                        Ok(Value::Integer(a | b))
+                    }
+                    TokenType::Caret => {
+                        let (a, b) =
+                            self.check_integer_operands(&operator, &left_child, &right_child)?;
+
+                        Ok(Value::Integer(a ^ b))
+                    }
+                    TokenType::LessLess | TokenType::GreaterGreater => {
+                        let (a, b) =
+                            self.check_integer_operands(&operator, &left_child, &right_child)?;
+
+                        let shifted = u32::try_from(b).ok().and_then(|b| {
+                            if operator.token_type == TokenType::LessLess {
+                                a.checked_shl(b)
+                            } else {
+                                a.checked_shr(b)
+                            }
+                        });
+
+                        match shifted {
+                            Some(value) => Ok(Value::Integer(value)),
+                            None => Err(InterpreterError {
+                                token: Some(operator.clone()),
+                                message: "Shift amount must be between 0 and 63.".into(),
+                            }),
+                        }
                     }
                     TokenType::BangEqual => Ok(Value::Boolean(left_child != right_child)),
                     TokenType::EqualEqual => Ok(Value::Boolean(left_child == right_child)),
                     _ => panic!("unreachable"),
                 }
             }
-            Expression::Variable(name) => {
-                return self.environment.get(&name);
-            }
+            Expression::Variable(name) => self.environment.get(&name),
             Expression::Assign { name, right } => {
                 let value = self.evaluate(*right)?;
 
                 self.environment.assign(&name, &value)?;
 
-                return Ok(value);
+                Ok(value)
             }
             Expression::Logical {
                 left,
@@ -258,7 +718,7 @@ impl Interpreter {
                 right,
             } => {
                 let left_value = self.evaluate(*left)?;
-                let is_left_truthy = self.is_truthy(left_value.clone());
+                let is_left_truthy = self.is_truthy(&left_value);
 
                 match operator.token_type {
                     TokenType::Or => {
@@ -275,6 +735,14 @@ impl Interpreter {
 
                         self.evaluate(*right)
                     }
+                    // both sides are always evaluated, there is nothing to short-circuit
+                    TokenType::Xor => {
+                        let right_value = self.evaluate(*right)?;
+
+                        Ok(Value::Boolean(
+                            is_left_truthy != self.is_truthy(&right_value),
+                        ))
+                    }
                     _ => panic!("unreachable"),
                 }
             }
@@ -293,21 +761,46 @@ impl Interpreter {
 
                 if let Value::Function(callable) = callee_value {
                     let arity = callable.borrow().arity();
-                    if arguments_values.len() != arity {
+                    if !arity.contains(&arguments_values.len()) {
+                        let expected = if arity.start() == arity.end() {
+                            arity.start().to_string()
+                        } else if *arity.end() == usize::MAX {
+                            format!("at least {}", arity.start())
+                        } else {
+                            format!("{} to {}", arity.start(), arity.end())
+                        };
+
                         return Err(InterpreterError {
                             token: Some(parenthesis.clone()),
                             message: format!(
-                                "Expected {arity} arguments but got {}.",
+                                "Expected {expected} arguments but got {}.",
                                 arguments_values.len()
                             ),
                         });
                     }
 
+                    if self.call_depth >= self.max_call_depth {
+                        return Err(InterpreterError {
+                            token: Some(parenthesis.clone()),
+                            message: "Stack overflow.".into(),
+                        });
+                    }
+
+                    let line = parenthesis.line;
+
+                    self.call_depth += 1;
                     let returned_value =
-                        callable
-                            .borrow()
-                            .call(self, arguments_values, parenthesis)?;
-                    return Ok(returned_value.unwrap_or(Value::Nil));
+                        callable.borrow().call(self, arguments_values, parenthesis);
+                    self.call_depth -= 1;
+
+                    if returned_value.is_err() {
+                        self.stack_trace.push(StackFrame {
+                            function: callable.borrow().as_str(),
+                            line,
+                        });
+                    }
+
+                    Ok(returned_value?.unwrap_or(Value::Nil))
                 } else {
                     Err(InterpreterError {
                         token: Some(parenthesis.clone()),
@@ -315,13 +808,214 @@ impl Interpreter {
                     })
                 }
             }
+            Expression::Get { object, name } => match self.evaluate(*object)? {
+                Value::String(receiver) => match native::StringMethod::bind(receiver, &name.lexeme)
+                {
+                    Some(method) => Ok(Value::Function(Rc::new(RefCell::new(method)))),
+                    None => Err(InterpreterError {
+                        message: format!("Undefined property '{}'.", name.lexeme),
+                        token: Some(name),
+                    }),
+                },
+                _ => Err(InterpreterError {
+                    token: Some(name),
+                    message: "Only strings have properties.".into(),
+                }),
+            },
+            Expression::List {
+                bracket: _,
+                elements,
+            } => {
+                let mut values: Vec<Value> = Vec::with_capacity(elements.len());
+
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+
+                Ok(Value::List(Rc::new(RefCell::new(values))))
+            }
+            Expression::Map { brace, entries } => {
+                let mut map = OrderedMap::new();
+
+                for (key, value) in entries {
+                    let key_value = self.evaluate(key)?;
+                    let key = self.check_hashable(&brace, &key_value)?;
+
+                    map.insert(key, self.evaluate(value)?);
+                }
+
+                Ok(Value::Map(Rc::new(RefCell::new(map))))
+            }
+            Expression::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object_value = self.evaluate(*object)?;
+                let index_value = self.evaluate(*index)?;
+
+                match object_value {
+                    Value::List(elements) => {
+                        let elements = elements.borrow();
+                        let position =
+                            self.check_list_index(&bracket, &index_value, elements.len())?;
+
+                        Ok(elements[position].clone())
+                    }
+                    Value::Map(map) => {
+                        let key = self.check_hashable(&bracket, &index_value)?;
+
+                        Ok(map.borrow().get(&key).cloned().unwrap_or(Value::Nil))
+                    }
+                    _ => Err(InterpreterError {
+                        token: Some(bracket),
+                        message: "Only lists and maps can be indexed.".into(),
+                    }),
+                }
+            }
+            Expression::SetIndex {
+                object,
+                bracket,
+                index,
+                right,
+            } => {
+                let object_value = self.evaluate(*object)?;
+                let index_value = self.evaluate(*index)?;
+
+                match object_value {
+                    Value::List(elements) => {
+                        let length = elements.borrow().len();
+                        let position = self.check_list_index(&bracket, &index_value, length)?;
+                        let value = self.evaluate(*right)?;
+
+                        // the right side may have resized the list
+                        match elements.borrow_mut().get_mut(position) {
+                            Some(element) => *element = value.clone(),
+                            None => {
+                                return Err(InterpreterError {
+                                    token: Some(bracket),
+                                    message: "List index out of range.".into(),
+                                })
+                            }
+                        }
+
+                        Ok(value)
+                    }
+                    Value::Map(map) => {
+                        let key = self.check_hashable(&bracket, &index_value)?;
+                        let value = self.evaluate(*right)?;
+
+                        map.borrow_mut().insert(key, value.clone());
+
+                        Ok(value)
+                    }
+                    _ => Err(InterpreterError {
+                        token: Some(bracket),
+                        message: "Only lists and maps can be indexed.".into(),
+                    }),
+                }
+            }
+            Expression::Update {
+                operator,
+                target,
+                prefix,
+            } => {
+                let (old, new) = match *target {
+                    Expression::Variable(name) => {
+                        let old = self.environment.get(&name)?;
+                        let new = self.step(&operator, &old)?;
+
+                        self.environment.assign(&name, &new)?;
+
+                        (old, new)
+                    }
+                    Expression::Index {
+                        object,
+                        bracket,
+                        index,
+                    } => {
+                        let object_value = self.evaluate(*object)?;
+                        let index_value = self.evaluate(*index)?;
+
+                        match object_value {
+                            Value::List(elements) => {
+                                let mut elements = elements.borrow_mut();
+                                let position =
+                                    self.check_list_index(&bracket, &index_value, elements.len())?;
+                                let old = elements[position].clone();
+                                let new = self.step(&operator, &old)?;
+
+                                elements[position] = new.clone();
+
+                                (old, new)
+                            }
+                            Value::Map(map) => {
+                                let key = self.check_hashable(&bracket, &index_value)?;
+                                let mut map = map.borrow_mut();
+                                let old = map.get(&key).cloned().unwrap_or(Value::Nil);
+                                let new = self.step(&operator, &old)?;
+
+                                map.insert(key, new.clone());
+
+                                (old, new)
+                            }
+                            _ => {
+                                return Err(InterpreterError {
+                                    token: Some(bracket),
+                                    message: "Only lists and maps can be indexed.".into(),
+                                })
+                            }
+                        }
+                    }
+                    _ => panic!("unreachable"),
+                };
+
+                Ok(if prefix { new } else { old })
+            }
+        }
+    }
+
+    /// `text` repeated `count` times, for `string * number` in either order.
+    fn repeat(&self, operator: &Token, text: &str, count: &Value) -> EvaluateInterpreterResult {
+        let Some(count) = count.as_integer().and_then(|n| usize::try_from(n).ok()) else {
+            return Err(InterpreterError {
+                token: Some(operator.clone()),
+                message: "Repeat count must be a non-negative integer.".into(),
+            });
+        };
+
+        match text.len().checked_mul(count) {
+            Some(length) if length <= MAX_REPEAT_LENGTH => {
+                Ok(Value::String(Rc::new(text.repeat(count))))
+            }
+            _ => Err(InterpreterError {
+                token: Some(operator.clone()),
+                message: "Repeated string is too long.".into(),
+            }),
+        }
+    }
+
+    /// Count one more iteration of a loop, failing once it goes over `max_loop_iterations`.
+    fn count_iteration(
+        &self,
+        keyword: &Token,
+        iterations: &mut usize,
+    ) -> Result<(), InterpreterError> {
+        *iterations += 1;
+
+        match self.max_loop_iterations {
+            Some(limit) if *iterations > limit => Err(InterpreterError {
+                token: Some(keyword.clone()),
+                message: "Loop iteration limit exceeded.".into(),
+            }),
+            _ => Ok(()),
         }
     }
 
-    pub fn is_truthy(&self, value: Value) -> bool {
+    pub fn is_truthy(&self, value: &Value) -> bool {
         match value {
             Value::Nil => false,
-            Value::Boolean(value) => value,
+            Value::Boolean(value) => *value,
             _ => true,
         }
     }
@@ -331,9 +1025,9 @@ impl Interpreter {
         operator: &Token,
         operand: &Value,
     ) -> Result<f64, InterpreterError> {
-        match operand {
-            Value::Number(x) => Ok(*x),
-            _ => Err(InterpreterError {
+        match operand.as_number() {
+            Some(x) => Ok(x),
+            None => Err(InterpreterError {
                 token: Some(operator.clone()),
                 message: "Operand must be a number.".into(),
             }),
@@ -346,12 +1040,204 @@ impl Interpreter {
         left: &Value,
         right: &Value,
     ) -> Result<(f64, f64), InterpreterError> {
-        match (left, right) {
-            (Value::Number(x), Value::Number(y)) => Ok((*x, *y)),
+        match (left.as_number(), right.as_number()) {
+            (Some(x), Some(y)) => Ok((x, y)),
             _ => Err(InterpreterError {
                 token: Some(operator.clone()),
                 message: "Operands must be a number.".into(),
             }),
         }
     }
+
+    /// Both operands as integers, whole floats included, for the bitwise operators.
+    pub fn check_integer_operands(
+        &self,
+        operator: &Token,
+        left: &Value,
+        right: &Value,
+    ) -> Result<(i64, i64), InterpreterError> {
+        match (left.as_integer(), right.as_integer()) {
+            (Some(a), Some(b)) => Ok((a, b)),
+            _ => Err(InterpreterError {
+                token: Some(operator.clone()),
+                message: "Operands must be integers.".into(),
+            }),
+        }
+    }
+
+    /// Order two numbers, or two strings lexicographically.
+    /// `None` when a NaN is involved, which makes every comparison false.
+    pub fn compare(
+        &self,
+        operator: &Token,
+        left: &Value,
+        right: &Value,
+    ) -> Result<Option<Ordering>, InterpreterError> {
+        if let (Value::String(a), Value::String(b)) = (left, right) {
+            return Ok(Some(a.cmp(b)));
+        }
+
+        let (x, y) = self.check_number_operands(operator, left, right)?;
+
+        Ok(x.partial_cmp(&y))
+    }
+
+    /// Apply an arithmetic operator.
+    /// Two integers stay an integer unless `integer` gives up (on overflow, or an inexact division), anything else is computed on floats.
+    pub fn arithmetic(
+        &self,
+        operator: &Token,
+        left: &Value,
+        right: &Value,
+        integer: fn(i64, i64) -> Option<i64>,
+        float: fn(f64, f64) -> f64,
+    ) -> EvaluateInterpreterResult {
+        if let (Value::Integer(a), Value::Integer(b)) = (left, right) {
+            if let Some(value) = integer(*a, *b) {
+                return Ok(Value::Integer(value));
+            }
+        }
+
+        let (x, y) = self.check_number_operands(operator, left, right)?;
+
+        Ok(Value::Number(float(x, y)))
+    }
+
+    /// The value after a `++` or `--`.
+    fn step(&self, operator: &Token, value: &Value) -> EvaluateInterpreterResult {
+        self.check_number_operand(operator, value)?;
+
+        if operator.token_type == TokenType::PlusPlus {
+            self.arithmetic(
+                operator,
+                value,
+                &Value::Integer(1),
+                i64::checked_add,
+                |x, y| x + y,
+            )
+        } else {
+            self.arithmetic(
+                operator,
+                value,
+                &Value::Integer(1),
+                i64::checked_sub,
+                |x, y| x - y,
+            )
+        }
+    }
+
+    /// A list index as a position, it must be a whole number inside the list.
+    pub fn check_list_index(
+        &self,
+        token: &Token,
+        index: &Value,
+        length: usize,
+    ) -> Result<usize, InterpreterError> {
+        let Some(position) = index.as_integer() else {
+            return Err(InterpreterError {
+                token: Some(token.clone()),
+                message: "List index must be an integer.".into(),
+            });
+        };
+
+        match usize::try_from(position) {
+            Ok(position) if position < length => Ok(position),
+            _ => Err(InterpreterError {
+                token: Some(token.clone()),
+                message: "List index out of range.".into(),
+            }),
+        }
+    }
+
+    pub fn check_hashable(
+        &self,
+        token: &Token,
+        key: &Value,
+    ) -> Result<HashableValue, InterpreterError> {
+        HashableValue::try_from(key).map_err(|_| InterpreterError {
+            token: Some(token.clone()),
+            message: "Map key must be nil, a boolean, a number or a string.".into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str) -> EvaluateInterpreterResult {
+        run_with(&mut Interpreter::new(), source)
+    }
+
+    fn run_with(interpreter: &mut Interpreter, source: &str) -> EvaluateInterpreterResult {
+        let tokens = Scanner::new(source.into()).scan_tokens();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        interpreter.run_statements(statements)
+    }
+
+    #[test]
+    fn run_statements_returns_last_expression_value() {
+        let value = run("1 + 1; var x = 5; x * 2;").unwrap();
+
+        assert_eq!(value.to_string(), "10");
+    }
+
+    #[test]
+    fn run_statements_returns_nil_without_expression() {
+        let value = run("var x = 5;").unwrap();
+
+        assert_eq!(value, Value::Nil);
+    }
+
+    #[test]
+    fn string_coercion_off_rejects_mixed_operands() {
+        let error = run(r#""a" + 1;"#).unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "Operands must be two numbers or two strings."
+        );
+    }
+
+    #[test]
+    fn string_coercion_on_stringifies_the_other_operand() {
+        let mut interpreter = Interpreter::new();
+        interpreter.string_coercion = true;
+
+        let value = run_with(&mut interpreter, r#"2.5 + "b";"#).unwrap();
+        assert_eq!(value.to_string(), "2.5b");
+
+        let value = run_with(&mut interpreter, r#""a" + 1;"#).unwrap();
+        assert_eq!(value.to_string(), "a1");
+    }
+
+    #[test]
+    fn colors_forced_on_wraps_in_ansi_escapes() {
+        let mut interpreter = Interpreter::new();
+        interpreter.colors = Some(true);
+
+        let value = run_with(&mut interpreter, r#"color("hi", "red");"#).unwrap();
+
+        assert_eq!(value.to_string(), "\x1b[31mhi\x1b[0m");
+    }
+
+    #[test]
+    fn colors_forced_off_returns_plain_text() {
+        let mut interpreter = Interpreter::new();
+        interpreter.colors = Some(false);
+
+        let value = run_with(&mut interpreter, r#"color("hi", "red");"#).unwrap();
+
+        assert_eq!(value.to_string(), "hi");
+    }
+
+    #[test]
+    fn repeat_rejects_oversized_strings() {
+        for source in [r#""ab" * 0x7fffffffffffffff;"#, r#""ab" * 1e15;"#] {
+            let error = run(source).unwrap_err();
+
+            assert_eq!(error.message, "Repeated string is too long.");
+        }
+    }
 }
@@ -0,0 +1,518 @@
+use crate::{Expression, Literal, Parameter, Statement, Token, TokenType};
+
+/// Minimal hand-written JSON output, so tooling can consume the interpreter's structures without extra dependencies.
+/// `Cargo.toml` has to stay as CodeCrafters ships it, which rules out serde and a cargo feature to gate this.
+pub trait ToJson {
+    fn to_json(&self) -> String;
+}
+
+/// Quote and escape a string as a JSON string literal.
+pub fn json_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                output.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => output.push(character),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+/// Build an AST node object, tagged with its `type`.
+fn node(kind: &str, fields: &[(&str, String)]) -> String {
+    let mut output = format!("{{\"type\": {}", json_string(kind));
+
+    for (name, value) in fields {
+        output.push_str(&format!(", {}: {value}", json_string(name)));
+    }
+
+    output.push('}');
+    output
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> String {
+        let items: Vec<String> = self.iter().map(ToJson::to_json).collect();
+
+        format!("[{}]", items.join(", "))
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> String {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> String {
+        match self {
+            Some(value) => value.to_json(),
+            None => "null".into(),
+        }
+    }
+}
+
+impl ToJson for TokenType {
+    fn to_json(&self) -> String {
+        json_string(&self.to_string())
+    }
+}
+
+impl ToJson for Literal {
+    fn to_json(&self) -> String {
+        match self {
+            Literal::Nil => "null".into(),
+            Literal::Boolean(value) => value.to_string(),
+            Literal::String(value) => json_string(value),
+            // JSON has no infinity nor NaN, like JSON.stringify they become null
+            Literal::Number(value) if !value.is_finite() => "null".into(),
+            Literal::Number(value) => value.to_string(),
+            Literal::Integer(value) => value.to_string(),
+        }
+    }
+}
+
+impl ToJson for Token {
+    fn to_json(&self) -> String {
+        node(
+            &self.token_type.to_string(),
+            &[
+                ("lexeme", json_string(&self.lexeme)),
+                ("line", self.line.to_string()),
+                ("start", self.start.to_string()),
+                ("end", self.end.to_string()),
+                ("literal", self.literal.to_json()),
+            ],
+        )
+    }
+}
+
+impl ToJson for Expression {
+    fn to_json(&self) -> String {
+        match self {
+            Expression::Literal(value) => node("Literal", &[("value", value.to_json())]),
+            Expression::Grouping(expression) => {
+                node("Grouping", &[("expression", expression.to_json())])
+            }
+            Expression::Unary { operator, right } => node(
+                "Unary",
+                &[("operator", operator.to_json()), ("right", right.to_json())],
+            ),
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => node(
+                "Binary",
+                &[
+                    ("left", left.to_json()),
+                    ("operator", operator.to_json()),
+                    ("right", right.to_json()),
+                ],
+            ),
+            Expression::Variable(name) => node("Variable", &[("name", name.to_json())]),
+            Expression::Assign { name, right } => node(
+                "Assign",
+                &[("name", name.to_json()), ("right", right.to_json())],
+            ),
+            Expression::Logical {
+                left,
+                operator,
+                right,
+            } => node(
+                "Logical",
+                &[
+                    ("left", left.to_json()),
+                    ("operator", operator.to_json()),
+                    ("right", right.to_json()),
+                ],
+            ),
+            Expression::Call {
+                callee,
+                parenthesis,
+                arguments,
+            } => node(
+                "Call",
+                &[
+                    ("callee", callee.to_json()),
+                    ("parenthesis", parenthesis.to_json()),
+                    ("arguments", arguments.to_json()),
+                ],
+            ),
+            Expression::Get { object, name } => node(
+                "Get",
+                &[("object", object.to_json()), ("name", name.to_json())],
+            ),
+            Expression::List { bracket, elements } => node(
+                "List",
+                &[
+                    ("bracket", bracket.to_json()),
+                    ("elements", elements.to_json()),
+                ],
+            ),
+            Expression::Map { brace, entries } => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{{\"key\": {}, \"value\": {}}}",
+                            key.to_json(),
+                            value.to_json()
+                        )
+                    })
+                    .collect();
+
+                node(
+                    "Map",
+                    &[
+                        ("brace", brace.to_json()),
+                        ("entries", format!("[{}]", entries.join(", "))),
+                    ],
+                )
+            }
+            Expression::Index {
+                object,
+                bracket,
+                index,
+            } => node(
+                "Index",
+                &[
+                    ("object", object.to_json()),
+                    ("bracket", bracket.to_json()),
+                    ("index", index.to_json()),
+                ],
+            ),
+            Expression::SetIndex {
+                object,
+                bracket,
+                index,
+                right,
+            } => node(
+                "SetIndex",
+                &[
+                    ("object", object.to_json()),
+                    ("bracket", bracket.to_json()),
+                    ("index", index.to_json()),
+                    ("right", right.to_json()),
+                ],
+            ),
+            Expression::Update {
+                operator,
+                target,
+                prefix,
+            } => node(
+                "Update",
+                &[
+                    ("operator", operator.to_json()),
+                    ("target", target.to_json()),
+                    ("prefix", prefix.to_string()),
+                ],
+            ),
+        }
+    }
+}
+
+impl ToJson for Parameter {
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"name\": {}, \"default\": {}, \"rest\": {}}}",
+            self.name.to_json(),
+            self.default.to_json(),
+            self.rest
+        )
+    }
+}
+
+impl ToJson for Statement {
+    fn to_json(&self) -> String {
+        match self {
+            Statement::Expression(expression) => {
+                node("Expression", &[("expression", expression.to_json())])
+            }
+            Statement::Function {
+                name,
+                parameters,
+                body,
+            } => node(
+                "Function",
+                &[
+                    ("name", name.to_json()),
+                    ("parameters", parameters.to_json()),
+                    ("body", body.to_json()),
+                ],
+            ),
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => node(
+                "If",
+                &[
+                    ("condition", condition.to_json()),
+                    ("then_branch", then_branch.to_json()),
+                    ("else_branch", else_branch.to_json()),
+                ],
+            ),
+            Statement::Print(expression) => node("Print", &[("expression", expression.to_json())]),
+            Statement::Variable { name, initializer } => node(
+                "Variable",
+                &[
+                    ("name", name.to_json()),
+                    ("initializer", initializer.to_json()),
+                ],
+            ),
+            Statement::Constant { name, initializer } => node(
+                "Constant",
+                &[
+                    ("name", name.to_json()),
+                    ("initializer", initializer.to_json()),
+                ],
+            ),
+            Statement::Return { keyword, value } => node(
+                "Return",
+                &[("keyword", keyword.to_json()), ("value", value.to_json())],
+            ),
+            Statement::While {
+                keyword,
+                condition,
+                body,
+                increment,
+            } => node(
+                "While",
+                &[
+                    ("keyword", keyword.to_json()),
+                    ("condition", condition.to_json()),
+                    ("body", body.to_json()),
+                    ("increment", increment.to_json()),
+                ],
+            ),
+            Statement::DoWhile {
+                keyword,
+                body,
+                condition,
+            } => node(
+                "DoWhile",
+                &[
+                    ("keyword", keyword.to_json()),
+                    ("body", body.to_json()),
+                    ("condition", condition.to_json()),
+                ],
+            ),
+            Statement::ForIn {
+                name,
+                keyword,
+                iterable,
+                body,
+            } => node(
+                "ForIn",
+                &[
+                    ("name", name.to_json()),
+                    ("keyword", keyword.to_json()),
+                    ("iterable", iterable.to_json()),
+                    ("body", body.to_json()),
+                ],
+            ),
+            Statement::Switch {
+                discriminant,
+                cases,
+                default,
+            } => {
+                let cases: Vec<String> = cases
+                    .iter()
+                    .map(|(value, body)| {
+                        format!(
+                            "{{\"value\": {}, \"body\": {}}}",
+                            value.to_json(),
+                            body.to_json()
+                        )
+                    })
+                    .collect();
+
+                node(
+                    "Switch",
+                    &[
+                        ("discriminant", discriminant.to_json()),
+                        ("cases", format!("[{}]", cases.join(", "))),
+                        (
+                            "default",
+                            default.as_deref().map_or("null".into(), ToJson::to_json),
+                        ),
+                    ],
+                )
+            }
+            Statement::Throw { keyword, value } => node(
+                "Throw",
+                &[("keyword", keyword.to_json()), ("value", value.to_json())],
+            ),
+            Statement::Try {
+                body,
+                name,
+                handler,
+            } => node(
+                "Try",
+                &[
+                    ("body", body.to_json()),
+                    ("name", name.to_json()),
+                    ("handler", handler.to_json()),
+                ],
+            ),
+            Statement::Block(statements) => node("Block", &[("statements", statements.to_json())]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Scanner};
+
+    /// Check that `text` is a single JSON value, walking the JSON grammar.
+    fn is_json(text: &str) -> bool {
+        let bytes = text.as_bytes();
+        let mut position = 0;
+
+        value(bytes, &mut position).is_some() && skip_spaces(bytes, &mut position) == bytes.len()
+    }
+
+    fn skip_spaces(bytes: &[u8], position: &mut usize) -> usize {
+        while bytes.get(*position).is_some_and(u8::is_ascii_whitespace) {
+            *position += 1;
+        }
+
+        *position
+    }
+
+    fn expect(bytes: &[u8], position: &mut usize, text: &str) -> Option<()> {
+        let end = *position + text.len();
+        (bytes.get(*position..end)? == text.as_bytes()).then(|| *position = end)
+    }
+
+    fn value(bytes: &[u8], position: &mut usize) -> Option<()> {
+        skip_spaces(bytes, position);
+
+        match *bytes.get(*position)? {
+            b'{' => sequence(bytes, position, b'}', |bytes, position| {
+                string(bytes, position)?;
+                skip_spaces(bytes, position);
+                expect(bytes, position, ":")?;
+                value(bytes, position)
+            }),
+            b'[' => sequence(bytes, position, b']', value),
+            b'"' => string(bytes, position),
+            b't' => expect(bytes, position, "true"),
+            b'f' => expect(bytes, position, "false"),
+            b'n' => expect(bytes, position, "null"),
+            _ => number(bytes, position),
+        }
+    }
+
+    /// An object or an array, from its opening character to `close`.
+    fn sequence(
+        bytes: &[u8],
+        position: &mut usize,
+        close: u8,
+        item: fn(&[u8], &mut usize) -> Option<()>,
+    ) -> Option<()> {
+        *position += 1;
+
+        if bytes.get(skip_spaces(bytes, position)) == Some(&close) {
+            *position += 1;
+            return Some(());
+        }
+
+        loop {
+            item(bytes, position)?;
+
+            match bytes.get(skip_spaces(bytes, position))? {
+                b',' => *position += 1,
+                byte if *byte == close => {
+                    *position += 1;
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(bytes: &[u8], position: &mut usize) -> Option<()> {
+        skip_spaces(bytes, position);
+        expect(bytes, position, "\"")?;
+
+        loop {
+            match *bytes.get(*position)? {
+                b'"' => {
+                    *position += 1;
+                    return Some(());
+                }
+                b'\\' => *position += 2,
+                byte if byte < 0x20 => return None,
+                _ => *position += 1,
+            }
+        }
+    }
+
+    fn number(bytes: &[u8], position: &mut usize) -> Option<()> {
+        let digits = |position: &mut usize| {
+            let start = *position;
+            while bytes.get(*position).is_some_and(u8::is_ascii_digit) {
+                *position += 1;
+            }
+
+            (*position > start).then_some(())
+        };
+
+        let _ = expect(bytes, position, "-");
+        digits(position)?;
+
+        if expect(bytes, position, ".").is_some() {
+            digits(position)?;
+        }
+
+        if matches!(bytes.get(*position), Some(b'e' | b'E')) {
+            *position += 1;
+            if matches!(bytes.get(*position), Some(b'+' | b'-')) {
+                *position += 1;
+            }
+
+            digits(position)?;
+        }
+
+        Some(())
+    }
+
+    /// The `parse --json` output of a program.
+    fn parse_json(source: &str) -> String {
+        let tokens = Scanner::new(source.into()).scan_tokens();
+
+        Parser::new(tokens).parse().unwrap().to_json()
+    }
+
+    #[test]
+    fn parse_output_is_valid_json() {
+        let output = parse_json(r#"var a = [1, "two\n", nil]; print -a[0] * 2.5 + 1e400;"#);
+
+        assert!(is_json(&output), "{output}");
+    }
+
+    #[test]
+    fn non_finite_numbers_are_null() {
+        assert!(!is_json(r#"{"type": "Literal", "value": inf}"#));
+
+        let output = parse_json("print 1e400;");
+
+        assert!(is_json(&output), "{output}");
+        assert!(output.contains(r#"{"type": "Literal", "value": null}"#));
+    }
+}
@@ -3,16 +3,18 @@ pub mod expression;
 pub mod function;
 pub mod grammar;
 pub mod interpreter;
+pub mod json;
 pub mod parser;
 pub mod scanner;
 pub mod statement;
 pub mod value;
 
-pub use environment::Environment;
+pub use environment::{Environment, EnvironmentSnapshot};
 pub use expression::*;
 pub use function::*;
 pub use grammar::*;
 pub use interpreter::*;
+pub use json::*;
 pub use parser::*;
 pub use scanner::*;
 pub use statement::*;
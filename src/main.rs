@@ -1,32 +1,85 @@
 use std::env;
 use std::fs;
-use std::io::{self, Write};
 use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use interpreter_starter_rust::{Interpreter, Value, Parser, Scanner};
+use interpreter_starter_rust::{Interpreter, Parser, Scanner, StackFrame, ToJson};
+
+/// Deep Lox recursion uses a lot of native stack, especially in debug builds.
+/// Give the interpreter enough room to reach `Interpreter::max_call_depth` and report a clean error instead.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
 
 fn main() {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+/// Frames shown at both ends of a long stack trace, the middle is elided.
+/// The trace is only printed with `--trace`, so the runtime error output stays the one the stages expect.
+const STACK_TRACE_EDGE: usize = 10;
+
+fn print_stack_trace(frames: &[StackFrame]) {
+    for (index, frame) in frames.iter().enumerate() {
+        if frames.len() > STACK_TRACE_EDGE * 2 && index == STACK_TRACE_EDGE {
+            eprintln!("  ... {} more", frames.len() - STACK_TRACE_EDGE * 2);
+        }
+
+        if index < STACK_TRACE_EDGE || index >= frames.len() - STACK_TRACE_EDGE {
+            eprintln!("  {frame}");
+        }
+    }
+}
+
+/// Report how long a phase of `run --time` took.
+fn print_timing(phase: &str, elapsed: Duration) {
+    eprintln!("[time] {phase}: {:.3}ms", elapsed.as_secs_f64() * 1000.0);
+}
+
+fn run() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
-        writeln!(io::stderr(), "Usage: {} tokenize <filename>", args[0]).unwrap();
+        eprintln!("Usage: {} tokenize <filename>", args[0]);
         return;
     }
 
     let command = &args[1];
-    let filename = &args[2];
+    let (flags, positionals): (Vec<&String>, Vec<&String>) =
+        args[2..].iter().partition(|argument| argument.starts_with("--"));
+    // `--json` can't sit behind a cargo feature, Cargo.toml must stay as CodeCrafters ships it
+    let has_flag = |name: &str| flags.iter().any(|flag| *flag == name);
+    // flags written `--name=value`
+    let flag_value = |name: &str| {
+        flags
+            .iter()
+            .find_map(|flag| flag.strip_prefix(name)?.strip_prefix('='))
+    };
+
+    let Some(filename) = positionals.first() else {
+        eprintln!("Usage: {} {} <filename>", args[0], command);
+        return;
+    };
 
     match command.as_str() {
         "tokenize" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
 
             let mut scanner = Scanner::new(file_contents);
             let tokens = scanner.scan_tokens();
 
-            for token in tokens {
-                println!("{}", token);
+            if has_flag("--json") {
+                println!("{}", tokens.to_json());
+            } else {
+                for token in tokens {
+                    println!("{}", token);
+                }
             }
 
             if scanner.had_error {
@@ -35,7 +88,7 @@ fn main() {
         }
         "parse" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
 
@@ -47,17 +100,28 @@ fn main() {
             }
 
             let mut parser = Parser::new(tokens);
-            match parser.expression() {
-                Ok(root) => println!("{}", root),
-                Err(error) => {
-                    eprintln!("{error}");
-                    exit(65);
+
+            if has_flag("--json") {
+                match parser.parse() {
+                    Ok(statements) => println!("{}", statements.to_json()),
+                    Err(error) => {
+                        eprintln!("{error}");
+                        exit(65);
+                    }
+                }
+            } else {
+                match parser.leading_expression() {
+                    Ok(root) => println!("{}", root),
+                    Err(error) => {
+                        eprintln!("{error}");
+                        exit(65);
+                    }
                 }
             }
         }
         "evaluate" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
 
@@ -69,7 +133,7 @@ fn main() {
             }
 
             let mut parser = Parser::new(tokens);
-            let root = match parser.expression() {
+            let root = match parser.leading_expression() {
                 Ok(root) => root,
                 Err(error) => {
                     eprintln!("{error}");
@@ -79,10 +143,7 @@ fn main() {
 
             let mut interpreter = Interpreter::new();
             match interpreter.evaluate(root) {
-                Ok(value) => match value {
-                    Value::Number(x) => println!("{}", x),
-                    _ => println!("{}", value),
-                },
+                Ok(value) => println!("{value}"),
                 Err(error) => {
                     eprintln!("{error}");
 
@@ -90,35 +151,85 @@ fn main() {
                         eprintln!("[line {}]", token.line);
                     }
 
+                    if has_flag("--trace") {
+                        print_stack_trace(&interpreter.stack_trace);
+                    }
+
                     exit(70);
                 }
             }
         }
         "run" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
 
+            let time = has_flag("--time");
+            let max_loop_iterations = match flag_value("--max-loop-iterations") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(limit) => Some(limit),
+                    Err(_) => {
+                        eprintln!("Invalid --max-loop-iterations value: {value}");
+                        return;
+                    }
+                },
+                None => None,
+            };
+
+            let start = Instant::now();
             let mut scanner = Scanner::new(file_contents);
             let tokens = scanner.scan_tokens();
 
+            if time {
+                print_timing("scan", start.elapsed());
+            }
+
             if scanner.had_error {
                 exit(65);
             }
 
+            let start = Instant::now();
             let mut parser = Parser::new(tokens);
-            let statements = match parser.parse() {
-                Ok(statements) => statements,
-                Err(error) => {
+            let mut had_parse_error = false;
+            let statements = if has_flag("--continue-on-error") {
+                let (statements, errors) = parser.parse_all();
+
+                for error in &errors {
                     eprintln!("{error}");
-                    exit(65);
+                }
+
+                had_parse_error = !errors.is_empty();
+                statements
+            } else {
+                match parser.parse() {
+                    Ok(statements) => statements,
+                    Err(error) => {
+                        eprintln!("{error}");
+                        exit(65);
+                    }
                 }
             };
 
+            if time {
+                print_timing("parse", start.elapsed());
+            }
+
+            let start = Instant::now();
             let mut interpreter = Interpreter::new();
-            match interpreter.interpret(statements) {
-                Ok(_) => {},
+            interpreter.max_loop_iterations = max_loop_iterations;
+            let result = interpreter.interpret(statements);
+
+            if time {
+                print_timing("interpret", start.elapsed());
+            }
+
+            match result {
+                Ok(_) => {
+                    if had_parse_error {
+                        exit(65);
+                    }
+                }
                 Err(error) => {
                     eprintln!("{error}");
 
@@ -126,13 +237,41 @@ fn main() {
                         eprintln!("[line {}]", token.line);
                     }
 
+                    if has_flag("--trace") {
+                        print_stack_trace(&interpreter.stack_trace);
+                    }
+
                     exit(70);
                 }
             }
         }
+        "check" => {
+            let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
+                eprintln!("Failed to read file {}", filename);
+                String::new()
+            });
+
+            let mut scanner = Scanner::new(file_contents);
+            let tokens = scanner.scan_tokens();
+
+            if scanner.had_error {
+                exit(65);
+            }
+
+            // only parse, reporting every error instead of stopping at the first one
+            let mut parser = Parser::new(tokens);
+            let (_, errors) = parser.parse_all();
+
+            for error in &errors {
+                eprintln!("{error}");
+            }
+
+            if !errors.is_empty() {
+                exit(65);
+            }
+        }
         _ => {
-            writeln!(io::stderr(), "Unknown command: {}", command).unwrap();
-            return;
+            eprintln!("Unknown command: {}", command);
         }
     }
 }
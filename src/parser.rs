@@ -1,11 +1,12 @@
 use std::vec::Vec;
 
-use crate::{Expression, Literal, Statement, Token, TokenType};
+use crate::{Expression, Literal, Parameter, Statement, Token, TokenType};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    max_params: usize,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -17,7 +18,16 @@ type ExpressionParserResult = Result<Expression, ParseError>;
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, current: 0 }
+        Self::with_max_params(tokens, 255)
+    }
+
+    /// Same as `new`, but with a different cap on the number of function parameters and call arguments.
+    pub fn with_max_params(tokens: Vec<Token>, max_params: usize) -> Self {
+        Parser {
+            tokens,
+            current: 0,
+            max_params,
+        }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Statement>, ParseError> {
@@ -31,6 +41,36 @@ impl Parser {
         Ok(statements)
     }
 
+    /// Parse a source made of exactly one expression, anything left after it is an error.
+    pub fn parse_expression(&mut self) -> ExpressionParserResult {
+        let expression = self.leading_expression()?;
+
+        if !self.is_at_end() {
+            return Err(self.error(self.peek(), "Expect end of expression."));
+        }
+
+        Ok(expression)
+    }
+
+    /// Parse the whole program without stopping at the first error.
+    /// After each error the parser skips to the next statement boundary, so the statements that did parse are still returned.
+    pub fn parse_all(&mut self) -> (Vec<Statement>, Vec<ParseError>) {
+        let mut statements: Vec<Statement> = Vec::new();
+        let mut errors: Vec<ParseError> = Vec::new();
+
+        while !self.is_at_end() {
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    errors.push(error);
+                    self.synchronize();
+                }
+            }
+        }
+
+        (statements, errors)
+    }
+
     pub fn declaration(&mut self) -> StatementParserResult {
         if self.match_(&[&TokenType::Fun]) {
             return self.function("function");
@@ -40,6 +80,10 @@ impl Parser {
             return self.variable();
         }
 
+        if self.match_(&[&TokenType::Const]) {
+            return self.constant();
+        }
+
         self.statement()
     }
 
@@ -56,17 +100,60 @@ impl Parser {
             format!("Expect '(' after {kind} name.").as_str(),
         )?;
 
-        let mut parameters: Vec<Token> = Vec::new();
+        let mut parameters: Vec<Parameter> = Vec::new();
+        let mut first_extra: Option<Token> = None;
         if !self.check(&TokenType::RightParen) {
             loop {
-                if parameters.len() >= 255 {
-                    return Err(self.error(self.peek(), "Can't have more than 255 parameters."));
+                if parameters.len() == self.max_params {
+                    first_extra = Some(self.peek().clone());
                 }
 
-                parameters.push(
-                    self.consume(&TokenType::Identifier, "Expect parameter name.")?
-                        .clone(),
-                );
+                if self.match_ellipsis() {
+                    let name = self
+                        .consume(&TokenType::Identifier, "Expect rest parameter name.")?
+                        .clone();
+
+                    if self.check(&TokenType::Equal) {
+                        return Err(
+                            self.error(self.peek(), "Rest parameter can't have a default value.")
+                        );
+                    }
+
+                    if self.check(&TokenType::Comma) {
+                        return Err(self.error(self.peek(), "Rest parameter must be the last one."));
+                    }
+
+                    parameters.push(Parameter {
+                        name,
+                        default: None,
+                        rest: true,
+                    });
+
+                    break;
+                }
+
+                let name = self
+                    .consume(&TokenType::Identifier, "Expect parameter name.")?
+                    .clone();
+
+                let mut default: Option<Expression> = None;
+                if self.match_(&[&TokenType::Equal]) {
+                    default = Some(self.expression()?);
+                } else if parameters
+                    .iter()
+                    .any(|parameter| parameter.default.is_some())
+                {
+                    return Err(self.error(
+                        &name,
+                        "Parameter without a default value can't follow one with a default value.",
+                    ));
+                }
+
+                parameters.push(Parameter {
+                    name,
+                    default,
+                    rest: false,
+                });
 
                 if !self.match_(&[&TokenType::Comma]) {
                     break;
@@ -74,6 +161,8 @@ impl Parser {
             }
         }
 
+        self.check_max_params(first_extra, parameters.len(), "parameters")?;
+
         self.consume(&TokenType::RightParen, "Expect ')' after parameters.")?;
         self.consume(
             &TokenType::LeftBrace,
@@ -110,7 +199,25 @@ impl Parser {
             return self.while_();
         }
 
-        if self.match_(&[&TokenType::LeftBrace]) {
+        if self.match_(&[&TokenType::Do]) {
+            return self.do_while();
+        }
+
+        if self.match_(&[&TokenType::Switch]) {
+            return self.switch();
+        }
+
+        if self.match_(&[&TokenType::Throw]) {
+            return self.throw();
+        }
+
+        if self.match_(&[&TokenType::Try]) {
+            return self.try_();
+        }
+
+        // a map statement like `{"a": 1};` is told apart from a block by its `key :`
+        if self.check(&TokenType::LeftBrace) && !self.is_map_entry_start() {
+            self.advance();
             return Ok(Statement::Block(self.block()?));
         }
 
@@ -118,12 +225,17 @@ impl Parser {
     }
 
     pub fn for_(&mut self) -> StatementParserResult {
+        let keyword = self.previous().clone();
         self.consume(&TokenType::LeftParen, "Expect '(' after 'for'.")?;
 
         let initializer: Option<Statement>;
         if self.match_(&[&TokenType::Semicolon]) {
             initializer = None;
         } else if self.match_(&[&TokenType::Var]) {
+            if self.check_next(&TokenType::In) {
+                return self.for_in();
+            }
+
             initializer = Some(self.variable()?);
         } else {
             initializer = Some(self.expression_statement()?);
@@ -131,27 +243,26 @@ impl Parser {
 
         let mut condition = Expression::Literal(Literal::Boolean(true));
         if !self.check(&TokenType::Semicolon) {
-            condition = self.expression()?;
+            condition = self.leading_expression()?;
         }
 
         self.consume(&TokenType::Semicolon, "Expect ';' after loop condition.")?;
 
         let mut increment: Option<Expression> = None;
         if !self.check(&TokenType::RightParen) {
-            increment = Some(self.expression()?);
+            increment = Some(self.leading_expression()?);
         }
 
         self.consume(&TokenType::RightParen, "Expect ')' after for clauses.")?;
 
-        let mut body = self.statement()?;
-
-        if let Some(expression) = increment {
-            body = Statement::Block(vec![body, Statement::Expression(expression)]);
-        }
+        let body = self.statement()?;
 
-        body = Statement::While {
+        // the increment stays apart from the body, so skipping the rest of the body can't skip it
+        let mut body = Statement::While {
+            keyword,
             condition,
             body: Box::new(body),
+            increment,
         };
 
         if let Some(expression) = initializer {
@@ -161,6 +272,28 @@ impl Parser {
         Ok(body)
     }
 
+    /// `for (var name in iterable) body`, the `for (var` being already consumed.
+    pub fn for_in(&mut self) -> StatementParserResult {
+        let name = self
+            .consume(&TokenType::Identifier, "Expect variable name.")?
+            .clone();
+        let keyword = self
+            .consume(&TokenType::In, "Expect 'in' after variable name.")?
+            .clone();
+
+        let iterable = self.expression()?;
+        self.consume(&TokenType::RightParen, "Expect ')' after for clauses.")?;
+
+        let body = self.statement()?;
+
+        Ok(Statement::ForIn {
+            name,
+            keyword,
+            iterable,
+            body: Box::new(body),
+        })
+    }
+
     pub fn if_(&mut self) -> StatementParserResult {
         self.consume(&TokenType::LeftParen, "Expect '(' after 'if'.")?;
         let condition = self.expression()?;
@@ -201,7 +334,94 @@ impl Parser {
         Ok(Statement::Return { keyword, value })
     }
 
+    pub fn switch(&mut self) -> StatementParserResult {
+        self.consume(&TokenType::LeftParen, "Expect '(' after 'switch'.")?;
+        let discriminant = self.expression()?;
+        self.consume(&TokenType::RightParen, "Expect ')' after switch value.")?;
+        self.consume(&TokenType::LeftBrace, "Expect '{' before switch cases.")?;
+
+        let mut cases: Vec<(Expression, Vec<Statement>)> = Vec::new();
+        let mut default: Option<Vec<Statement>> = None;
+
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            if self.match_(&[&TokenType::Case]) {
+                let value = self.expression()?;
+                self.consume(&TokenType::Colon, "Expect ':' after case value.")?;
+
+                cases.push((value, self.case_body()?));
+            } else if self.match_(&[&TokenType::Default]) {
+                if default.is_some() {
+                    let message = "Can't have more than one default case.";
+                    return Err(self.error(self.previous(), message));
+                }
+
+                self.consume(&TokenType::Colon, "Expect ':' after 'default'.")?;
+
+                default = Some(self.case_body()?);
+            } else {
+                return Err(self.error(self.peek(), "Expect 'case' or 'default'."));
+            }
+        }
+
+        self.consume(&TokenType::RightBrace, "Expect '}' after switch cases.")?;
+
+        Ok(Statement::Switch {
+            discriminant,
+            cases,
+            default,
+        })
+    }
+
+    /// The statements of a switch case, up to the next case or the end of the switch.
+    fn case_body(&mut self) -> Result<Vec<Statement>, ParseError> {
+        let mut statements: Vec<Statement> = Vec::new();
+
+        while !self.check(&TokenType::Case)
+            && !self.check(&TokenType::Default)
+            && !self.check(&TokenType::RightBrace)
+            && !self.is_at_end()
+        {
+            statements.push(self.declaration()?);
+        }
+
+        Ok(statements)
+    }
+
+    pub fn throw(&mut self) -> StatementParserResult {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+
+        self.consume(&TokenType::Semicolon, "Expect ';' after thrown value.")?;
+
+        Ok(Statement::Throw { keyword, value })
+    }
+
+    pub fn try_(&mut self) -> StatementParserResult {
+        self.consume(&TokenType::LeftBrace, "Expect '{' after 'try'.")?;
+        let body = self.block()?;
+
+        self.consume(&TokenType::Catch, "Expect 'catch' after try block.")?;
+        self.consume(&TokenType::LeftParen, "Expect '(' after 'catch'.")?;
+        let name = self
+            .consume(&TokenType::Identifier, "Expect caught variable name.")?
+            .clone();
+        self.consume(
+            &TokenType::RightParen,
+            "Expect ')' after caught variable name.",
+        )?;
+
+        self.consume(&TokenType::LeftBrace, "Expect '{' after catch clause.")?;
+        let handler = self.block()?;
+
+        Ok(Statement::Try {
+            body,
+            name,
+            handler,
+        })
+    }
+
     pub fn while_(&mut self) -> StatementParserResult {
+        let keyword = self.previous().clone();
         self.consume(&TokenType::LeftParen, "Expect '(' after 'while'.")?;
         let condition = self.expression()?;
         self.consume(&TokenType::RightParen, "Expect ')' after if condition.")?;
@@ -209,8 +429,30 @@ impl Parser {
         let body = self.statement()?;
 
         Ok(Statement::While {
+            keyword,
             condition,
             body: Box::new(body),
+            increment: None,
+        })
+    }
+
+    pub fn do_while(&mut self) -> StatementParserResult {
+        let keyword = self.previous().clone();
+        let body = self.statement()?;
+
+        self.consume(&TokenType::While, "Expect 'while' after do body.")?;
+        self.consume(&TokenType::LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(&TokenType::RightParen, "Expect ')' after condition.")?;
+        self.consume(
+            &TokenType::Semicolon,
+            "Expect ';' after do-while condition.",
+        )?;
+
+        Ok(Statement::DoWhile {
+            keyword,
+            body: Box::new(body),
+            condition,
         })
     }
 
@@ -235,6 +477,22 @@ impl Parser {
         })
     }
 
+    pub fn constant(&mut self) -> StatementParserResult {
+        let name = self
+            .consume(&TokenType::Identifier, "Expect constant name.")?
+            .clone();
+
+        self.consume(&TokenType::Equal, "Expect '=' after constant name.")?;
+        let initializer = self.expression()?;
+
+        self.consume(
+            &TokenType::Semicolon,
+            "Expect ';' after constant declaration.",
+        )?;
+
+        Ok(Statement::Constant { name, initializer })
+    }
+
     pub fn block(&mut self) -> Result<Vec<Statement>, ParseError> {
         let mut statements: Vec<Statement> = Vec::new();
 
@@ -248,7 +506,7 @@ impl Parser {
     }
 
     pub fn expression_statement(&mut self) -> StatementParserResult {
-        let expression = self.expression()?;
+        let expression = self.leading_expression()?;
 
         self.consume(&TokenType::Semicolon, "Expect ';' after expression.")?;
 
@@ -259,6 +517,16 @@ impl Parser {
         self.assignment()
     }
 
+    /// An expression standing where a statement could, like a for clause or the source of `parse` and `evaluate`.
+    /// A `{` there only opens a map when a `key :` follows, so `{}` keeps being an error rather than an empty map.
+    pub fn leading_expression(&mut self) -> ExpressionParserResult {
+        if self.check(&TokenType::LeftBrace) && !self.is_map_entry_start() {
+            return Err(self.error(self.peek(), "Expect expression."));
+        }
+
+        self.expression()
+    }
+
     pub fn assignment(&mut self) -> ExpressionParserResult {
         let expression = self.or()?;
 
@@ -273,6 +541,20 @@ impl Parser {
                 });
             }
 
+            if let Expression::Index {
+                object,
+                bracket,
+                index,
+            } = expression
+            {
+                return Ok(Expression::SetIndex {
+                    object,
+                    bracket,
+                    index,
+                    right: Box::new(value),
+                });
+            }
+
             return Err(self.error(&equals, "Invalid assignment target."));
         }
 
@@ -280,9 +562,26 @@ impl Parser {
     }
 
     pub fn or(&mut self) -> ExpressionParserResult {
-        let mut expression = self.and()?;
+        let mut expression = self.xor()?;
 
         while self.match_(&[&TokenType::Or]) {
+            let operator = self.previous().clone();
+            let right = self.xor()?;
+
+            expression = Expression::Logical {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expression)
+    }
+
+    pub fn xor(&mut self) -> ExpressionParserResult {
+        let mut expression = self.and()?;
+
+        while self.match_(&[&TokenType::Xor]) {
             let operator = self.previous().clone();
             let right = self.and()?;
 
@@ -331,7 +630,7 @@ impl Parser {
     }
 
     pub fn comparison(&mut self) -> ExpressionParserResult {
-        let mut expression = self.term()?;
+        let mut expression = self.bit_or()?;
 
         while self.match_(&[
             &TokenType::Greater,
@@ -340,6 +639,73 @@ impl Parser {
             &TokenType::LessEqual,
         ]) {
             let operator = self.previous().clone();
+            let right = self.bit_or()?;
+
+            expression = Expression::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            }
+        }
+
+        Ok(expression)
+    }
+
+    pub fn bit_or(&mut self) -> ExpressionParserResult {
+        let mut expression = self.bit_xor()?;
+
+        while self.match_(&[&TokenType::Pipe]) {
+            let operator = self.previous().clone();
+            let right = self.bit_xor()?;
+
+            expression = Expression::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            }
+        }
+
+        Ok(expression)
+    }
+
+    pub fn bit_xor(&mut self) -> ExpressionParserResult {
+        let mut expression = self.bit_and()?;
+
+        while self.match_(&[&TokenType::Caret]) {
+            let operator = self.previous().clone();
+            let right = self.bit_and()?;
+
+            expression = Expression::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            }
+        }
+
+        Ok(expression)
+    }
+
+    pub fn bit_and(&mut self) -> ExpressionParserResult {
+        let mut expression = self.shift()?;
+
+        while self.match_(&[&TokenType::Ampersand]) {
+            let operator = self.previous().clone();
+            let right = self.shift()?;
+
+            expression = Expression::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            }
+        }
+
+        Ok(expression)
+    }
+
+    pub fn shift(&mut self) -> ExpressionParserResult {
+        let mut expression = self.term()?;
+
+        while let Some(operator) = self.match_shift() {
             let right = self.term()?;
 
             expression = Expression::Binary {
@@ -387,6 +753,23 @@ impl Parser {
     }
 
     pub fn unary(&mut self) -> ExpressionParserResult {
+        if let Some(operator) = self.match_update() {
+            let minuses = self.tokens[self.current - 2..self.current].to_vec();
+            let target = self.unary()?;
+
+            // `--` in front of something that can't be decremented is a double negation, like `--5`
+            if operator.token_type == TokenType::MinusMinus && !is_assignable(&target) {
+                return Ok(minuses.into_iter().rev().fold(target, |right, operator| {
+                    Expression::Unary {
+                        operator,
+                        right: Box::new(right),
+                    }
+                }));
+            }
+
+            return self.update(operator, target, true);
+        }
+
         if self.match_(&[&TokenType::Bang, &TokenType::Minus]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
@@ -397,26 +780,118 @@ impl Parser {
             });
         }
 
-        self.call()
+        self.power()
+    }
+
+    pub fn power(&mut self) -> ExpressionParserResult {
+        let expression = self.postfix()?;
+
+        // right-associative, and binds tighter than unary on its left: `-2 ** 2` is `-(2 ** 2)`
+        if let Some(operator) = self.match_adjacent(&TokenType::Star, TokenType::StarStar, "**") {
+            let right = self.unary()?;
+
+            return Ok(Expression::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expression)
+    }
+
+    pub fn postfix(&mut self) -> ExpressionParserResult {
+        let expression = self.call()?;
+
+        // a `--` is only a decrement after something that can be decremented and with no operand after it,
+        // so `1--1` and `a--1` stay subtractions of a negation
+        let operand_follows = self
+            .tokens
+            .get(self.current + 2)
+            .is_some_and(|token| starts_operand(&token.token_type));
+        if self.check(&TokenType::Minus) && (!is_assignable(&expression) || operand_follows) {
+            return Ok(expression);
+        }
+
+        match self.match_update() {
+            Some(operator) => self.update(operator, expression, false),
+            None => Ok(expression),
+        }
+    }
+
+    /// Build an increment or decrement, only variables and indexes can be updated.
+    fn update(&self, operator: Token, target: Expression, prefix: bool) -> ExpressionParserResult {
+        if !is_assignable(&target) {
+            let message = if operator.token_type == TokenType::PlusPlus {
+                "Invalid increment target."
+            } else {
+                "Invalid decrement target."
+            };
+
+            return Err(self.error(&operator, message));
+        }
+
+        Ok(Expression::Update {
+            operator,
+            target: Box::new(target),
+            prefix,
+        })
     }
 
     pub fn call(&mut self) -> ExpressionParserResult {
         let mut expression = self.primary()?;
 
-        while self.match_(&[&TokenType::LeftParen]) {
-            expression = self.finish_call(expression)?
+        loop {
+            if self.match_(&[&TokenType::LeftParen]) {
+                expression = self.finish_call(expression)?
+            } else if self.match_(&[&TokenType::LeftBracket]) {
+                expression = self.finish_index(expression)?
+            } else if self.match_(&[&TokenType::Dot]) {
+                let name = self
+                    .consume(&TokenType::Identifier, "Expect property name after '.'.")?
+                    .clone();
+
+                expression = Expression::Get {
+                    object: Box::new(expression),
+                    name,
+                }
+            } else {
+                break;
+            }
         }
 
         Ok(expression)
     }
 
+    /// Fail at `first_extra`, the first parameter or argument over `max_params`, if there is one.
+    /// The whole list is parsed before checking, so the error can report the count.
+    fn check_max_params(
+        &self,
+        first_extra: Option<Token>,
+        count: usize,
+        kind: &str,
+    ) -> Result<(), ParseError> {
+        match first_extra {
+            Some(token) => {
+                let message = format!(
+                    "Can't have more than {} {kind}, got {count}.",
+                    self.max_params
+                );
+
+                Err(self.error(&token, &message))
+            }
+            None => Ok(()),
+        }
+    }
+
     pub fn finish_call(&mut self, callee: Expression) -> ExpressionParserResult {
         let mut arguments: Vec<Expression> = Vec::new();
+        let mut first_extra: Option<Token> = None;
 
         if !self.check(&TokenType::RightParen) {
             loop {
-                if arguments.len() >= 255 {
-                    return Err(self.error(self.peek(), "Can't have more than 255 arguments."));
+                if arguments.len() == self.max_params {
+                    first_extra = Some(self.peek().clone());
                 }
 
                 arguments.push(self.expression()?);
@@ -427,6 +902,8 @@ impl Parser {
             }
         }
 
+        self.check_max_params(first_extra, arguments.len(), "arguments")?;
+
         let parenthesis = self.consume(&TokenType::RightParen, "Expect ')' after arguments.")?;
 
         Ok(Expression::Call {
@@ -436,6 +913,93 @@ impl Parser {
         })
     }
 
+    pub fn finish_index(&mut self, object: Expression) -> ExpressionParserResult {
+        let index = self.expression()?;
+        let bracket = self.consume(&TokenType::RightBracket, "Expect ']' after index.")?;
+
+        Ok(Expression::Index {
+            object: Box::new(object),
+            bracket: bracket.clone(),
+            index: Box::new(index),
+        })
+    }
+
+    /// Whether the `{` at the current token can open a map: a `}` or the start of a key expression follows.
+    /// Only one token is looked at, otherwise `Expect expression.` is reported at the `{`.
+    pub fn is_map_start(&self) -> bool {
+        self.tokens.get(self.current + 1).is_some_and(|token| {
+            token.token_type == TokenType::RightBrace || starts_operand(&token.token_type)
+        })
+    }
+
+    /// Whether the `{` at the current token is followed by a single token key and a `:`.
+    pub fn is_map_entry_start(&self) -> bool {
+        let is = |offset: usize, token_types: &[TokenType]| {
+            self.tokens
+                .get(self.current + offset)
+                .is_some_and(|token| token_types.contains(&token.token_type))
+        };
+
+        is(
+            1,
+            &[
+                TokenType::False,
+                TokenType::True,
+                TokenType::Nil,
+                TokenType::Number,
+                TokenType::String,
+                TokenType::Identifier,
+            ],
+        ) && is(2, &[TokenType::Colon])
+    }
+
+    pub fn list(&mut self) -> ExpressionParserResult {
+        let bracket = self.previous().clone();
+
+        let mut elements: Vec<Expression> = Vec::new();
+        if !self.check(&TokenType::RightBracket) {
+            loop {
+                elements.push(self.expression()?);
+
+                if !self.match_(&[&TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(&TokenType::RightBracket, "Expect ']' after list elements.")?;
+
+        Ok(Expression::List { bracket, elements })
+    }
+
+    /// A `{` in expression position always opens a map, `{}` being the empty one.
+    /// Where a statement could start, it is a block unless a single token key and a `:` follow it, so `{"a": 1};` is a map statement
+    /// and `{ print 1; }` a block. Wrap a map in parentheses to use any other key, or the empty map, as an expression statement.
+    pub fn map(&mut self) -> ExpressionParserResult {
+        let brace = self.previous().clone();
+
+        let mut entries: Vec<(Expression, Expression)> = Vec::new();
+        if self.match_(&[&TokenType::RightBrace]) {
+            return Ok(Expression::Map { brace, entries });
+        }
+
+        loop {
+            let key = self.expression()?;
+            self.consume(&TokenType::Colon, "Expect ':' after map key.")?;
+            let value = self.expression()?;
+
+            entries.push((key, value));
+
+            if !self.match_(&[&TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(&TokenType::RightBrace, "Expect '}' after map entries.")?;
+
+        Ok(Expression::Map { brace, entries })
+    }
+
     pub fn primary(&mut self) -> ExpressionParserResult {
         if self.match_(&[&TokenType::False]) {
             return Ok(Expression::Literal(Literal::Boolean(false)));
@@ -466,9 +1030,48 @@ impl Parser {
             return Ok(Expression::Grouping(Box::new(expression)));
         }
 
+        if self.match_(&[&TokenType::LeftBracket]) {
+            return self.list();
+        }
+
+        if self.check(&TokenType::LeftBrace) && self.is_map_start() {
+            self.advance();
+            return self.map();
+        }
+
         Err(self.error(self.peek(), "Expect expression."))
     }
 
+    /// Discard tokens until what is probably the start of the next statement.
+    pub fn synchronize(&mut self) {
+        self.advance();
+
+        while !self.is_at_end() {
+            if self.previous().token_type == TokenType::Semicolon {
+                return;
+            }
+
+            match self.peek().token_type {
+                TokenType::Class
+                | TokenType::Const
+                | TokenType::Do
+                | TokenType::Fun
+                | TokenType::Var
+                | TokenType::For
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Print
+                | TokenType::Return
+                | TokenType::Switch
+                | TokenType::Throw
+                | TokenType::Try => return,
+                _ => {}
+            }
+
+            self.advance();
+        }
+    }
+
     pub fn match_(&mut self, token_types: &[&TokenType]) -> bool {
         for token_type in token_types {
             if self.check(token_type) {
@@ -496,6 +1099,82 @@ impl Parser {
         self.peek().token_type == *token_type
     }
 
+    /// Match two `part` tokens written without a space in between and merge them into a single `token_type` token.
+    /// The scanner keeps `*`, `<`, `>`, `+` and `-` one by one, so the tokenize output of the original grammar is unchanged.
+    pub fn match_adjacent(
+        &mut self,
+        part: &TokenType,
+        token_type: TokenType,
+        lexeme: &str,
+    ) -> Option<Token> {
+        let adjacent = self.check(part)
+            && self.check_next(part)
+            && self.tokens[self.current].end == self.tokens[self.current + 1].start;
+
+        if !adjacent {
+            return None;
+        }
+
+        let (line, start) = {
+            let first = self.advance();
+            (first.line, first.start)
+        };
+        let end = self.advance().end;
+
+        Some(Token::new(
+            token_type,
+            lexeme.into(),
+            None,
+            line,
+            start,
+            end,
+        ))
+    }
+
+    /// Match a `<<` or `>>` shift operator and merge it into a single token.
+    /// The scanner keeps emitting `<` and `>` one by one, so sequences like `<<=` still tokenize as `<` followed by `<=`.
+    pub fn match_shift(&mut self) -> Option<Token> {
+        self.match_adjacent(&TokenType::Less, TokenType::LessLess, "<<")
+            .or_else(|| self.match_adjacent(&TokenType::Greater, TokenType::GreaterGreater, ">>"))
+    }
+
+    /// Match a `++` or `--` and merge it into a single token.
+    /// Like shifts, the scanner emits two tokens, they must be written without a space in between.
+    pub fn match_update(&mut self) -> Option<Token> {
+        self.match_adjacent(&TokenType::Plus, TokenType::PlusPlus, "++")
+            .or_else(|| self.match_adjacent(&TokenType::Minus, TokenType::MinusMinus, "--"))
+    }
+
+    /// Match a `...` rest marker, scanned as three dots without a space in between.
+    pub fn match_ellipsis(&mut self) -> bool {
+        // each dot must start where the previous one ended, `. . .` is not a rest marker
+        let is_dot = |offset: usize| {
+            self.tokens
+                .get(self.current + offset)
+                .is_some_and(|token| token.token_type == TokenType::Dot)
+                && (offset == 0
+                    || self.tokens[self.current + offset - 1].end
+                        == self.tokens[self.current + offset].start)
+        };
+
+        if !(is_dot(0) && is_dot(1) && is_dot(2)) {
+            return false;
+        }
+
+        for _ in 0..3 {
+            self.advance();
+        }
+
+        true
+    }
+
+    pub fn check_next(&self, token_type: &TokenType) -> bool {
+        match self.tokens.get(self.current + 1) {
+            Some(token) => token.token_type == *token_type,
+            None => false,
+        }
+    }
+
     pub fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
@@ -526,6 +1205,69 @@ impl Parser {
             )
         };
 
-        return ParseError(error_message);
+        ParseError(error_message)
+    }
+}
+
+/// Whether an expression, or an operator applying to the one after it, can start with the token.
+fn starts_operand(token_type: &TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::False
+            | TokenType::True
+            | TokenType::Nil
+            | TokenType::Number
+            | TokenType::String
+            | TokenType::Identifier
+            | TokenType::LeftParen
+            | TokenType::LeftBracket
+            | TokenType::LeftBrace
+            | TokenType::Bang
+            | TokenType::Minus
+            | TokenType::Plus
+    )
+}
+
+/// Whether `++` and `--` can update the expression: a variable, a list element or a map entry.
+fn is_assignable(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Variable(_) | Expression::Index { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+
+    fn parse_with_max_params(
+        source: &str,
+        max_params: usize,
+    ) -> Result<Vec<Statement>, ParseError> {
+        let tokens = Scanner::new(source.into()).scan_tokens();
+
+        Parser::with_max_params(tokens, max_params).parse()
+    }
+
+    #[test]
+    fn with_max_params_rejects_more_parameters() {
+        let error = parse_with_max_params("fun f(a, b, c) {}", 2).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "[line 1] Error at 'c': Can't have more than 2 parameters, got 3."
+        );
+    }
+
+    #[test]
+    fn with_max_params_accepts_the_limit() {
+        assert!(parse_with_max_params("fun f(a, b) {}", 2).is_ok());
+    }
+
+    #[test]
+    fn with_max_params_rejects_more_arguments() {
+        assert!(parse_with_max_params("f(1, 2);", 2).is_ok());
+        assert!(parse_with_max_params("f(1, 2, 3);", 2).is_err());
     }
 }
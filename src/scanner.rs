@@ -1,72 +1,98 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use crate::{Literal, Token, TokenType};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Scanner {
-    source: String,
-    source_len: usize,
+    /// Source decoded once, so positions are character indexes with constant time access.
+    source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
     pub had_error: bool,
     keywords: HashMap<&'static str, TokenType>,
+    /// Contents of the string literals seen so far, so identical literals share one allocation.
+    strings: HashSet<Rc<String>>,
 }
 
 impl Scanner {
     pub fn new(source: String) -> Self {
+        Self::with_keywords(source, Self::default_keywords())
+    }
+
+    /// Scan with a custom keyword table, for dialects that rename or add keywords.
+    /// Anything not in the table scans as an identifier.
+    pub fn with_keywords(source: String, keywords: HashMap<&'static str, TokenType>) -> Self {
         Scanner {
-            source_len: source.chars().count(),
-            source,
+            source: source.chars().collect(),
             tokens: Vec::new(),
             start: 0,
             current: 0,
             line: 1,
             had_error: false,
-            keywords: HashMap::from([
-                ("and", TokenType::And),
-                ("class", TokenType::Class),
-                ("else", TokenType::Else),
-                ("false", TokenType::False),
-                ("for", TokenType::For),
-                ("fun", TokenType::Fun),
-                ("if", TokenType::If),
-                ("nil", TokenType::Nil),
-                ("or", TokenType::Or),
-                ("print", TokenType::Print),
-                ("return", TokenType::Return),
-                ("super", TokenType::Super),
-                ("this", TokenType::This),
-                ("true", TokenType::True),
-                ("var", TokenType::Var),
-                ("while", TokenType::While),
-            ]),
+            keywords,
+            strings: HashSet::new(),
         }
     }
 
+    pub fn default_keywords() -> HashMap<&'static str, TokenType> {
+        HashMap::from([
+            ("and", TokenType::And),
+            ("case", TokenType::Case),
+            ("catch", TokenType::Catch),
+            ("class", TokenType::Class),
+            ("const", TokenType::Const),
+            ("default", TokenType::Default),
+            ("do", TokenType::Do),
+            ("else", TokenType::Else),
+            ("false", TokenType::False),
+            ("for", TokenType::For),
+            ("fun", TokenType::Fun),
+            ("if", TokenType::If),
+            ("in", TokenType::In),
+            ("nil", TokenType::Nil),
+            ("or", TokenType::Or),
+            ("print", TokenType::Print),
+            ("return", TokenType::Return),
+            ("super", TokenType::Super),
+            ("switch", TokenType::Switch),
+            ("this", TokenType::This),
+            ("throw", TokenType::Throw),
+            ("true", TokenType::True),
+            ("try", TokenType::Try),
+            ("var", TokenType::Var),
+            ("while", TokenType::While),
+            ("xor", TokenType::Xor),
+        ])
+    }
+
     pub fn is_at_end(&self) -> bool {
-        self.current >= self.source_len
+        self.current >= self.source.len()
     }
 
     pub fn text(&self) -> String {
-        self.source
-            .chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect::<String>()
+        self.source[self.start..self.current].iter().collect()
     }
 
+    /// Scan the whole source and give away the tokens, ending with an EOF token.
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         while !self.is_at_end() {
             self.start = self.current;
             self.scan_token();
         }
 
-        self.tokens
-            .push(Token::new(TokenType::Eof, "".into(), None, self.line));
+        self.tokens.push(Token::new(
+            TokenType::Eof,
+            "".into(),
+            None,
+            self.line,
+            self.current,
+            self.current,
+        ));
 
-        self.tokens.clone()
+        std::mem::take(&mut self.tokens)
     }
 
     pub fn scan_token(&mut self) {
@@ -77,6 +103,9 @@ impl Scanner {
             ')' => self.add_token(TokenType::RightParen, None),
             '{' => self.add_token(TokenType::LeftBrace, None),
             '}' => self.add_token(TokenType::RightBrace, None),
+            '[' => self.add_token(TokenType::LeftBracket, None),
+            ']' => self.add_token(TokenType::RightBracket, None),
+            ':' => self.add_token(TokenType::Colon, None),
             ',' => self.add_token(TokenType::Comma, None),
             '.' => self.add_token(TokenType::Dot, None),
             '-' => self.add_token(TokenType::Minus, None),
@@ -91,9 +120,17 @@ impl Scanner {
             '<' => self.add_token(TokenType::Less, None),
             '>' if self.match_('=') => self.add_token(TokenType::GreaterEqual, None),
             '>' => self.add_token(TokenType::Greater, None),
+            '&' => self.add_token(TokenType::Ampersand, None),
+            '|' => self.add_token(TokenType::Pipe, None),
+            '^' => self.add_token(TokenType::Caret, None),
             '/' if self.match_('/') => self.advance_next_line(),
             '/' => self.add_token(TokenType::Slash, None),
-            ' ' | '\r' | '\t' => (),
+            ' ' | '\t' => (),
+            // `\r\n` is a single line break, a lone `\r` is one too
+            '\r' => {
+                self.match_('\n');
+                self.line += 1
+            }
             '\n' => self.line += 1,
             '"' => self.string(),
             _ => {
@@ -109,29 +146,32 @@ impl Scanner {
     }
 
     fn advance(&mut self) -> char {
-        let index = self.current;
+        let character = self.source[self.current];
         self.current += 1;
-        self.source.chars().nth(index).unwrap()
+        character
     }
 
     fn advance_next_line(&mut self) {
-        while self.peek() != '\n' && !self.is_at_end() {
+        while self.line_break_length(0) == 0 && !self.is_at_end() {
             self.advance();
         }
     }
 
+    /// How many characters the line break starting `n` characters ahead spans, 0 if there is none.
+    fn line_break_length(&self, n: usize) -> usize {
+        match (self.peek_at(n), self.peek_at(n + 1)) {
+            ('\r', '\n') => 2,
+            ('\r', _) | ('\n', _) => 1,
+            _ => 0,
+        }
+    }
+
     fn peek(&self) -> char {
         self.peek_at(0)
     }
 
     fn peek_at(&self, n: usize) -> char {
-        let index = self.current + n;
-
-        if index >= self.source_len {
-            return '\0';
-        }
-
-        self.source.chars().nth(index).unwrap()
+        self.source.get(self.current + n).copied().unwrap_or('\0')
     }
 
     fn match_(&mut self, expected: char) -> bool {
@@ -139,7 +179,7 @@ impl Scanner {
             return false;
         }
 
-        if self.source.chars().nth(self.current).unwrap() != expected {
+        if self.source[self.current] != expected {
             return false;
         }
 
@@ -148,17 +188,43 @@ impl Scanner {
     }
 
     fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
-        self.tokens
-            .push(Token::new(token_type, self.text(), literal, self.line));
+        self.tokens.push(Token::new(
+            token_type,
+            self.text(),
+            literal,
+            self.line,
+            self.start,
+            self.current,
+        ));
     }
 
     fn string(&mut self) {
+        let mut value = String::new();
+
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
+            // a backslash right before a newline continues the string without keeping either
+            let continuation_length = self.line_break_length(1);
+            if self.peek() == '\\' && continuation_length != 0 {
+                // backslash and line break
+                for _ in 0..=continuation_length {
+                    self.advance();
+                }
+
                 self.line += 1;
+                continue;
             }
 
-            self.advance();
+            let line_break_length = self.line_break_length(0);
+            if line_break_length != 0 {
+                for _ in 0..line_break_length {
+                    value.push(self.advance());
+                }
+
+                self.line += 1;
+                continue;
+            }
+
+            value.push(self.advance());
         }
 
         if self.is_at_end() {
@@ -169,32 +235,137 @@ impl Scanner {
         // closing "
         self.advance();
 
-        let value = self
-            .source
-            .chars()
-            .skip(self.start + 1)
-            .take(self.current - self.start - 2)
-            .collect::<String>();
+        let value = self.intern(value);
+        self.add_token(TokenType::String, Some(Literal::String(value)))
+    }
 
-        self.add_token(TokenType::String, Some(Literal::String(value.into())))
+    fn intern(&mut self, value: String) -> Rc<String> {
+        if let Some(interned) = self.strings.get(&value) {
+            return interned.clone();
+        }
+
+        let interned = Rc::new(value);
+        self.strings.insert(interned.clone());
+        interned
     }
 
     fn number(&mut self) {
-        while self.is_number(self.peek()) {
-            self.advance();
+        if self.text() == "0" {
+            match self.peek() {
+                'x' | 'X' => return self.radix_number(16),
+                'b' | 'B' => return self.radix_number(2),
+                _ => {}
+            }
         }
 
-        if self.peek() == '.' && self.is_number(self.peek_at(1)) {
+        self.digits();
+
+        if self.peek() == '.' && (self.is_number(self.peek_at(1)) || self.peek_at(1) == '_') {
             // consume .
             self.advance();
 
-            while self.is_number(self.peek()) {
+            self.digits();
+        }
+
+        // an exponent is only consumed when digits follow, otherwise the `e` starts the next token
+        if matches!(self.peek(), 'e' | 'E') {
+            let sign_length = if matches!(self.peek_at(1), '+' | '-') {
+                1
+            } else {
+                0
+            };
+
+            if self.is_number(self.peek_at(1 + sign_length)) {
+                // consume e and sign
+                for _ in 0..=sign_length {
+                    self.advance();
+                }
+
+                self.digits();
+            }
+        }
+
+        let text = self.text();
+        if !Self::has_valid_separators(&text, |character| self.is_number(character)) {
+            self.error(
+                self.line,
+                format!("Invalid digit separator in number: {text}"),
+            );
+            return;
+        }
+
+        // whole literals are kept as integers as long as they fit
+        let digits = text.replace('_', "");
+        let literal = match digits.parse::<i64>() {
+            Ok(value) => Literal::Integer(value),
+            Err(_) => Literal::Number(digits.parse().unwrap()),
+        };
+
+        self.add_token(TokenType::Number, Some(literal));
+    }
+
+    /// Consume a run of digits, including any `_` separator, which are validated afterward.
+    fn digits(&mut self) {
+        while self.is_number(self.peek()) || self.peek() == '_' {
+            self.advance();
+        }
+    }
+
+    /// Scan the rest of a `0x` or `0b` integer literal, the leading `0` being already consumed.
+    fn radix_number(&mut self, radix: u32) {
+        // consume x or b
+        self.advance();
+
+        let digits_start = self.current;
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            self.advance();
+        }
+
+        // `0b12` or `0x1G` is one bad literal, not a number followed by another token
+        if self.is_alpha_or_number(self.peek()) {
+            while self.is_alpha_or_number(self.peek()) {
                 self.advance();
             }
+
+            let text = self.text();
+            self.error(self.line, format!("Invalid digit in number: {text}"));
+            return;
         }
 
-        let value: f64 = self.text().parse().unwrap();
-        self.add_token(TokenType::Number, Some(Literal::Number(value)));
+        let text = self.text();
+        let digits: String = text.chars().skip(2).collect();
+
+        if self.current == digits_start {
+            self.error(self.line, format!("Expect digits after '{text}'."));
+            return;
+        }
+
+        if !Self::has_valid_separators(&digits, |character| character.is_digit(radix)) {
+            self.error(
+                self.line,
+                format!("Invalid digit separator in number: {text}"),
+            );
+            return;
+        }
+
+        match i64::from_str_radix(&digits.replace('_', ""), radix) {
+            Ok(value) => self.add_token(TokenType::Number, Some(Literal::Integer(value))),
+            Err(_) => self.error(self.line, format!("Number is too large: {text}")),
+        }
+    }
+
+    /// A `_` is only allowed between two digits, so `1__0`, `1_`, and `1_.5` are all rejected.
+    fn has_valid_separators(text: &str, is_digit: impl Fn(char) -> bool) -> bool {
+        let characters: Vec<char> = text.chars().collect();
+
+        characters.iter().enumerate().all(|(index, character)| {
+            *character != '_'
+                || (index > 0
+                    && is_digit(characters[index - 1])
+                    && characters
+                        .get(index + 1)
+                        .is_some_and(|next| is_digit(*next)))
+        })
     }
 
     fn identifier(&mut self) {
@@ -211,16 +382,17 @@ impl Scanner {
         );
     }
 
+    /// Only ASCII digits start a number, other numeric characters are not valid Lox.
     fn is_number(&self, character: char) -> bool {
-        return character.is_numeric();
+        character.is_ascii_digit()
     }
 
     fn is_alpha(&self, character: char) -> bool {
-        return character.is_alphabetic() || character == '_';
+        character.is_alphabetic() || character == '_'
     }
 
     fn is_alpha_or_number(&self, character: char) -> bool {
-        return self.is_alpha(character) || self.is_number(character);
+        self.is_alpha(character) || self.is_number(character)
     }
 
     fn error(&mut self, line: usize, message: String) {
@@ -232,3 +404,23 @@ impl Scanner {
         self.had_error = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_keywords_maps_custom_keywords() {
+        let mut keywords = Scanner::default_keywords();
+        keywords.remove("fun");
+        keywords.insert("func", TokenType::Fun);
+
+        let tokens = Scanner::with_keywords("func fun".into(), keywords).scan_tokens();
+        let types: Vec<&TokenType> = tokens.iter().map(|token| &token.token_type).collect();
+
+        assert_eq!(
+            types,
+            [&TokenType::Fun, &TokenType::Identifier, &TokenType::Eof]
+        );
+    }
+}
@@ -1,14 +1,22 @@
 use {
     crate::{Expression, Token},
-    std::vec::Vec,
+    std::{fmt, vec::Vec},
 };
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameter {
+    pub name: Token,
+    pub default: Option<Expression>,
+    /// A `...name` rest parameter, always the last one, receives the extra arguments as a list.
+    pub rest: bool,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
     Expression(Expression),
     Function {
         name: Token,
-        parameters: Vec<Token>,
+        parameters: Vec<Parameter>,
         body: Vec<Statement>,
     },
     If {
@@ -21,13 +29,211 @@ pub enum Statement {
         name: Token,
         initializer: Option<Expression>,
     },
+    Constant {
+        name: Token,
+        initializer: Expression,
+    },
     Return {
         keyword: Token,
         value: Option<Expression>,
     },
     While {
+        /// The `while`, or `for` of the loop it was desugared from.
+        keyword: Token,
+        condition: Expression,
+        body: Box<Statement>,
+        /// The increment of a `for`, run after the body rather than as part of it.
+        increment: Option<Expression>,
+    },
+    DoWhile {
+        keyword: Token,
+        body: Box<Statement>,
         condition: Expression,
+    },
+    ForIn {
+        name: Token,
+        keyword: Token,
+        iterable: Expression,
         body: Box<Statement>,
     },
+    /// Only the first case equal to the discriminant runs, there is no fall-through.
+    Switch {
+        discriminant: Expression,
+        cases: Vec<(Expression, Vec<Statement>)>,
+        default: Option<Vec<Statement>>,
+    },
+    Throw {
+        keyword: Token,
+        value: Expression,
+    },
+    Try {
+        body: Vec<Statement>,
+        name: Token,
+        handler: Vec<Statement>,
+    },
     Block(Vec<Statement>),
 }
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.default {
+            Some(default) => write!(f, "{} = {default}", self.name.lexeme),
+            None if self.rest => write!(f, "...{}", self.name.lexeme),
+            None => write!(f, "{}", self.name.lexeme),
+        }
+    }
+}
+
+impl Statement {
+    /// Write the statement on its own lines, nested statements being indented by two more spaces.
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+
+        match self {
+            Statement::Expression(expression) => write!(f, "{indent}{expression}"),
+            Statement::Function {
+                name,
+                parameters,
+                body,
+            } => {
+                let parameters: Vec<String> = parameters.iter().map(ToString::to_string).collect();
+                write!(f, "{indent}fun {}({})", name.lexeme, parameters.join(", "))?;
+
+                for statement in body {
+                    writeln!(f)?;
+                    statement.fmt_indented(f, depth + 1)?;
+                }
+
+                Ok(())
+            }
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                writeln!(f, "{indent}if {condition}")?;
+                then_branch.fmt_body(f, depth)?;
+
+                if let Some(else_branch) = else_branch {
+                    writeln!(f, "\n{indent}else")?;
+                    else_branch.fmt_body(f, depth)?;
+                }
+
+                Ok(())
+            }
+            Statement::Print(expression) => write!(f, "{indent}print {expression}"),
+            Statement::Variable { name, initializer } => match initializer {
+                Some(initializer) => write!(f, "{indent}var {} = {initializer}", name.lexeme),
+                None => write!(f, "{indent}var {}", name.lexeme),
+            },
+            Statement::Constant { name, initializer } => {
+                write!(f, "{indent}const {} = {initializer}", name.lexeme)
+            }
+            Statement::Return { keyword: _, value } => match value {
+                Some(value) => write!(f, "{indent}return {value}"),
+                None => write!(f, "{indent}return"),
+            },
+            Statement::While {
+                keyword: _,
+                condition,
+                body,
+                increment,
+            } => {
+                match increment {
+                    Some(increment) => writeln!(f, "{indent}while {condition}; {increment}")?,
+                    None => writeln!(f, "{indent}while {condition}")?,
+                }
+
+                body.fmt_body(f, depth)
+            }
+            Statement::DoWhile {
+                keyword: _,
+                body,
+                condition,
+            } => {
+                writeln!(f, "{indent}do")?;
+                body.fmt_body(f, depth)?;
+                write!(f, "\n{indent}while {condition}")
+            }
+            Statement::ForIn {
+                name,
+                keyword: _,
+                iterable,
+                body,
+            } => {
+                writeln!(f, "{indent}for {} in {iterable}", name.lexeme)?;
+                body.fmt_body(f, depth)
+            }
+            Statement::Switch {
+                discriminant,
+                cases,
+                default,
+            } => {
+                write!(f, "{indent}switch {discriminant}")?;
+
+                let inner_indent = "  ".repeat(depth + 1);
+                let labelled = cases
+                    .iter()
+                    .map(|(value, body)| (format!("case {value}"), body))
+                    .chain(default.iter().map(|body| ("default".to_string(), body)));
+
+                for (label, body) in labelled {
+                    write!(f, "\n{inner_indent}{label}")?;
+
+                    for statement in body {
+                        writeln!(f)?;
+                        statement.fmt_indented(f, depth + 2)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Statement::Throw { keyword: _, value } => write!(f, "{indent}throw {value}"),
+            Statement::Try {
+                body,
+                name,
+                handler,
+            } => {
+                write!(f, "{indent}try")?;
+
+                for statement in body {
+                    writeln!(f)?;
+                    statement.fmt_indented(f, depth + 1)?;
+                }
+
+                write!(f, "\n{indent}catch {}", name.lexeme)?;
+
+                for statement in handler {
+                    writeln!(f)?;
+                    statement.fmt_indented(f, depth + 1)?;
+                }
+
+                Ok(())
+            }
+            Statement::Block(statements) => {
+                write!(f, "{indent}{{")?;
+
+                for statement in statements {
+                    writeln!(f)?;
+                    statement.fmt_indented(f, depth + 1)?;
+                }
+
+                write!(f, "\n{indent}}}")
+            }
+        }
+    }
+
+    /// Write the body of an `if`, `while` or `for`, a block lines up with its header instead of being indented twice.
+    fn fmt_body(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match self {
+            Statement::Block(_) => self.fmt_indented(f, depth),
+            _ => self.fmt_indented(f, depth + 1),
+        }
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{Callable, Literal};
 use core::fmt;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 #[derive(Debug, Clone)]
 pub enum Value {
@@ -8,7 +12,10 @@ pub enum Value {
     Boolean(bool),
     String(Rc<String>),
     Number(f64),
+    Integer(i64),
     Function(Rc<RefCell<dyn Callable>>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<OrderedMap>>),
 }
 
 impl From<Literal> for Value {
@@ -18,25 +25,112 @@ impl From<Literal> for Value {
             Literal::Boolean(value) => Value::Boolean(value),
             Literal::String(value) => Value::String(value),
             Literal::Number(value) => Value::Number(value),
+            Literal::Integer(value) => Value::Integer(value),
+        }
+    }
+}
+
+impl Value {
+    /// The numeric value of either number representation.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(value) => Some(*value),
+            Value::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer, if it is a whole number of either representation.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            Value::Number(value) if value.fract() == 0.0 && value.abs() < i64::MAX as f64 => {
+                Some(*value as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// The name of the value's type, as seen from Lox. Both number representations are a `number`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Boolean(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Number(_) | Value::Integer(_) => "number",
+            Value::Function(_) => "function",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
         }
     }
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut HashSet::new())
+    }
+}
+
+/// Two lists being compared, by address.
+type ListPair = (*const RefCell<Vec<Value>>, *const RefCell<Vec<Value>>);
+
+impl Value {
+    /// Equality that remembers the list pairs it already went into.
+    /// Meeting a pair again means the lists contain themselves the same way, so it is taken as equal instead of recursing forever.
+    fn equals(&self, other: &Self, compared: &mut HashSet<ListPair>) -> bool {
         match (self, other) {
             (Value::Nil, Value::Nil) => true,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
-            (Value::String(a), Value::String(b)) => a == b,
+            // interned literals share their allocation, the contents are only compared otherwise
+            (Value::String(a), Value::String(b)) => Rc::ptr_eq(a, b) || a == b,
             (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Integer(a), Value::Number(b)) | (Value::Number(b), Value::Integer(a)) => {
+                *a as f64 == *b
+            }
             (Value::Function(a), Value::Function(b)) => std::ptr::addr_eq(a.as_ptr(), b.as_ptr()),
+            // element by element, nested lists included
+            (Value::List(a), Value::List(b)) => {
+                if Rc::ptr_eq(a, b) || !compared.insert((Rc::as_ptr(a), Rc::as_ptr(b))) {
+                    return true;
+                }
+
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b, compared))
+            }
+            (Value::Map(a), Value::Map(b)) => Rc::ptr_eq(a, b),
             _ => false,
         }
     }
 }
 
+/// How numbers of either representation are shown, the one place shared by `print`, string conversions, tokens and syntax trees.
+/// Whole numbers are written bare, `42`, unless `fraction` asks for the `42.0` tokens and syntax trees use.
+pub fn format_number(value: impl fmt::Display, fraction: bool) -> String {
+    let text = value.to_string();
+
+    // floats are never written with an exponent, so only digits means a whole number, `inf` and `NaN` stay as they are
+    let whole = text
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || byte == b'-');
+
+    if fraction && whole {
+        text + ".0"
+    } else {
+        text
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, &mut Vec::new())
+    }
+}
+
+impl Value {
+    /// Write the value, `enclosing` holding the addresses of the containers being written around it.
+    /// A list or a map met again inside itself is written `[...]` or `{...}` instead of recursing forever.
+    fn write(&self, f: &mut fmt::Formatter<'_>, enclosing: &mut Vec<*const ()>) -> fmt::Result {
         match self {
             Value::Nil => write!(f, "nil"),
             Value::Boolean(value) => {
@@ -47,8 +141,181 @@ impl fmt::Display for Value {
                 }
             }
             Value::String(value) => write!(f, "{}", *value),
-            Value::Number(value) => write!(f, "{value}"),
+            Value::Number(value) => write!(f, "{}", format_number(value, false)),
+            Value::Integer(value) => write!(f, "{}", format_number(value, false)),
             Value::Function(value) => write!(f, "{}", value.borrow().as_str()),
+            Value::List(elements) => {
+                let address = Rc::as_ptr(elements) as *const ();
+                if enclosing.contains(&address) {
+                    return write!(f, "[...]");
+                }
+
+                enclosing.push(address);
+                write!(f, "[")?;
+
+                for (index, element) in elements.borrow().iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    element.write(f, enclosing)?;
+                }
+
+                enclosing.pop();
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                let address = Rc::as_ptr(entries) as *const ();
+                if enclosing.contains(&address) {
+                    return write!(f, "{{...}}");
+                }
+
+                enclosing.push(address);
+                write!(f, "{{")?;
+
+                for (index, (key, value)) in entries.borrow().iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{key}: ")?;
+                    value.write(f, enclosing)?;
+                }
+
+                enclosing.pop();
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// The entries of a map, kept in insertion order for printing and iteration.
+#[derive(Debug, Clone, Default)]
+pub struct OrderedMap {
+    keys: Vec<HashableValue>,
+    values: HashMap<HashableValue, Value>,
+}
+
+impl OrderedMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &HashableValue) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Set the value of a key, a new key goes after the existing ones.
+    pub fn insert(&mut self, key: HashableValue, value: Value) {
+        if self.values.insert(key.clone(), value).is_none() {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &HashableValue> {
+        self.keys.iter()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&HashableValue, &Value)> {
+        self.keys.iter().map(|key| (key, &self.values[key]))
+    }
+}
+
+/// The subset of values that can be used as a map key.
+/// Whole numbers are stored as integers so `2` and `2.0` address the same entry, the other numbers are compared by bit pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HashableValue {
+    Nil,
+    Boolean(bool),
+    Number(u64),
+    Integer(i64),
+    String(Rc<String>),
+}
+
+impl TryFrom<&Value> for HashableValue {
+    type Error = ();
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(HashableValue::Nil),
+            Value::Boolean(value) => Ok(HashableValue::Boolean(*value)),
+            Value::Number(value) if value.fract() == 0.0 && value.abs() < i64::MAX as f64 => {
+                Ok(HashableValue::Integer(*value as i64))
+            }
+            Value::Number(value) => Ok(HashableValue::Number(value.to_bits())),
+            Value::Integer(value) => Ok(HashableValue::Integer(*value)),
+            Value::String(value) => Ok(HashableValue::String(value.clone())),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&HashableValue> for Value {
+    fn from(key: &HashableValue) -> Self {
+        match key {
+            HashableValue::Nil => Value::Nil,
+            HashableValue::Boolean(value) => Value::Boolean(*value),
+            HashableValue::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            HashableValue::Integer(value) => Value::Integer(*value),
+            HashableValue::String(value) => Value::String(value.clone()),
+        }
+    }
+}
+
+impl fmt::Display for HashableValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Value::from(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Interpreter, Parser, Scanner, Value};
+
+    /// The number as `tokenize` shows its literal, and as `print` and `evaluate` show its value.
+    fn formats(source: &str) -> (String, String) {
+        let tokens = Scanner::new(source.into()).scan_tokens();
+        let literal = tokens[0].literal.clone().unwrap().to_string();
+
+        let expression = Parser::new(tokens).parse_expression().unwrap();
+        let value = Interpreter::new().evaluate(expression).unwrap();
+
+        (literal, value.to_string())
+    }
+
+    #[test]
+    fn tokens_and_values_format_numbers_alike() {
+        let cases = [
+            ("42", "42.0", "42"),
+            ("0.5", "0.5", "0.5"),
+            ("2.50", "2.5", "2.5"),
+            ("9007199254740993", "9007199254740993.0", "9007199254740993"),
+            ("1e21", "1000000000000000000000.0", "1000000000000000000000"),
+        ];
+
+        for (source, token, printed) in cases {
+            assert_eq!(formats(source), (token.into(), printed.into()));
         }
     }
+
+    fn run(source: &str) -> Value {
+        let tokens = Scanner::new(source.into()).scan_tokens();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        Interpreter::new().run_statements(statements).unwrap()
+    }
+
+    #[test]
+    fn self_containing_map_prints_an_ellipsis() {
+        let value = run(r#"var m = {"a": 1}; m["b"] = m; m;"#);
+
+        assert_eq!(value.to_string(), "{a: 1, b: {...}}");
+    }
+
+    #[test]
+    fn self_containing_list_prints_an_ellipsis() {
+        let value = run("var a = [1]; a[0] = a; var b = [2, a]; [b, b];");
+
+        assert_eq!(value.to_string(), "[[2, [[...]]], [2, [[...]]]]");
+    }
 }